@@ -0,0 +1,192 @@
+//! Derive macros for `aoc-common`.
+//! Use them through `aoc_common::captures`, which re-exports them next to their traits.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::{
+        parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, GenericArgument, LitStr,
+        Path, PathArguments, Type,
+};
+
+/// Build a struct from the named capture groups of a regex match.
+///
+/// Each field is filled from the group of the same name, converted with `FromStr`
+/// (or `TryFrom<&[u8]>` in bytes mode).
+/// `Option<T>` fields are `None` when their group didn't participate in the match.
+///
+/// ## Attributes
+/// - `#[captures(pattern = RE_NAME)]` on the struct: also implement `FromStr`
+///   (`TryFrom<&[u8]>` in bytes mode) by matching against that pattern.
+/// - `#[captures(bytes)]` on the struct: work from `bytes` captures instead of `str`.
+/// - `#[captures(group = "name")]` on a field: read a differently named group.
+#[proc_macro_derive(FromCaptures, attributes(captures))]
+pub fn derive_from_captures(input: TokenStream) -> TokenStream {
+        let input = parse_macro_input!(input as DeriveInput);
+        expand(&input).unwrap_or_else(syn::Error::into_compile_error)
+                      .into()
+}
+
+/// Struct-level `#[captures(..)]` settings.
+#[derive(Default)]
+struct StructOpts {
+        pattern: Option<Path>,
+        bytes:   bool,
+}
+
+fn struct_opts(input: &DeriveInput) -> syn::Result<StructOpts> {
+        let mut opts = StructOpts::default();
+        for attr in input.attrs
+                         .iter()
+                         .filter(|a| a.path().is_ident("captures"))
+        {
+                attr.parse_nested_meta(|meta| {
+                            if meta.path.is_ident("pattern") {
+                                    opts.pattern = Some(meta.value()?.parse()?);
+                                    Ok(())
+                            }
+                            else if meta.path.is_ident("bytes") {
+                                    opts.bytes = true;
+                                    Ok(())
+                            }
+                            else {
+                                    Err(meta.error("expected `pattern = <static>` or `bytes`"))
+                            }
+                    })?;
+        }
+        Ok(opts)
+}
+
+/// Group name for a field: `#[captures(group = "..")]` if given, else the field's own name.
+fn group_name(field: &syn::Field) -> syn::Result<LitStr> {
+        let ident = field.ident
+                         .as_ref()
+                         .expect("named fields only");
+        let mut group = LitStr::new(&ident.to_string(), ident.span());
+        for attr in field.attrs
+                         .iter()
+                         .filter(|a| a.path().is_ident("captures"))
+        {
+                attr.parse_nested_meta(|meta| {
+                            if meta.path.is_ident("group") {
+                                    group = meta.value()?.parse()?;
+                                    Ok(())
+                            }
+                            else {
+                                    Err(meta.error("expected `group = \"<name>\"`"))
+                            }
+                    })?;
+        }
+        Ok(group)
+}
+
+/// `T` if the type is written `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+        let Type::Path(tp) = ty
+        else {
+                return None;
+        };
+        let last = tp.path.segments.last()?;
+        if last.ident != "Option" {
+                return None;
+        }
+        let PathArguments::AngleBracketed(args) = &last.arguments
+        else {
+                return None;
+        };
+        match args.args.first()? {
+                GenericArgument::Type(inner) => Some(inner),
+                _ => None,
+        }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+        let Data::Struct(data) = &input.data
+        else {
+                return Err(syn::Error::new_spanned(&input.ident,
+                                                   "FromCaptures can only be derived for structs"));
+        };
+        let Fields::Named(fields) = &data.fields
+        else {
+                return Err(syn::Error::new(data.fields.span(),
+                                           "FromCaptures needs named fields to match group names"));
+        };
+        let opts = struct_opts(input)?;
+
+        let inits = fields.named
+                          .iter()
+                          .map(|field| {
+                                  let ident = &field.ident;
+                                  let group = group_name(field)?;
+                                  // spanned, so a type without `FromStr` points at its field
+                                  Ok(match option_inner(&field.ty) {
+                                          Some(inner) => quote_spanned! {field.ty.span()=>
+                                                  #ident: if caps.name(#group).is_some() {
+                                                          ::core::option::Option::Some(
+                                                                  pattern.parse_group::<#inner>(caps, #group)?)
+                                                  } else {
+                                                          ::core::option::Option::None
+                                                  }
+                                          },
+                                          None => {
+                                                  let ty = &field.ty;
+                                                  quote_spanned! {field.ty.span()=>
+                                                          #ident: pattern.parse_group::<#ty>(caps, #group)?
+                                                  }
+                                          },
+                                  })
+                          })
+                          .collect::<syn::Result<Vec<_>>>()?;
+
+        let name = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics
+                                                              .split_for_impl();
+        let (trait_, method, pattern_ty, caps_ty) = if opts.bytes {
+                (quote!(FromBytesCaptures),
+                 quote!(from_bytes_captures),
+                 quote!(::aoc_common::patterns::BytesPattern),
+                 quote!(::aoc_common::regex::bytes::Captures<'_>))
+        }
+        else {
+                (quote!(FromCaptures),
+                 quote!(from_captures),
+                 quote!(::aoc_common::patterns::Pattern),
+                 quote!(::aoc_common::regex::Captures<'_>))
+        };
+
+        let mut out = quote! {
+                impl #impl_generics ::aoc_common::captures::#trait_ for #name #ty_generics #where_clause {
+                        fn #method(pattern: &#pattern_ty,
+                                   caps: &#caps_ty)
+                                   -> ::core::result::Result<Self, ::aoc_common::patterns::PatternError> {
+                                ::core::result::Result::Ok(Self { #(#inits,)* })
+                        }
+                }
+        };
+
+        if let Some(pattern) = opts.pattern {
+                out.extend(if opts.bytes {
+                                   quote! {
+                                           impl #impl_generics ::core::convert::TryFrom<&[u8]> for #name #ty_generics #where_clause {
+                                                   type Error = ::aoc_common::patterns::PatternError;
+
+                                                   fn try_from(hay: &[u8]) -> ::core::result::Result<Self, Self::Error> {
+                                                           #pattern.extract(hay)
+                                                   }
+                                           }
+                                   }
+                           }
+                           else {
+                                   quote! {
+                                           impl #impl_generics ::core::str::FromStr for #name #ty_generics #where_clause {
+                                                   type Err = ::aoc_common::patterns::PatternError;
+
+                                                   fn from_str(hay: &str) -> ::core::result::Result<Self, Self::Err> {
+                                                           #pattern.extract(hay)
+                                                   }
+                                           }
+                                   }
+                           });
+        }
+        Ok(out)
+}
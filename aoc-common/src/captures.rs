@@ -0,0 +1,189 @@
+//! Typed extraction from named capture groups.
+//!
+//! `#[derive(FromCaptures)]` turns a struct into the target of a pattern match,
+//! field by field, instead of a `caps.name("x").expect(..).as_str().parse().expect(..)` chain
+//! per field.
+//! Parse failures carry the matched text and the failing group's span.
+//!
+//! ## e.g.
+//! ```
+//! use aoc_common::{captures::FromCaptures, patterns};
+//!
+//! patterns! {
+//!         RE_VAL_MAP = r"^(?<outstart>\d+) (?<instart>\d+) (?<length>\d+)";
+//! }
+//!
+//! #[derive(FromCaptures, Debug, PartialEq)]
+//! #[captures(pattern = RE_VAL_MAP)]
+//! struct ValMap {
+//!         outstart: i64,
+//!         instart:  i64,
+//!         length:   i64,
+//! }
+//!
+//! let vm: ValMap = "50 98 2".parse().unwrap();
+//! assert_eq!(vm, ValMap { outstart: 50, instart: 98, length: 2 });
+//! assert!("50 98 x".parse::<ValMap>().is_err());
+//! ```
+
+use std::fmt::Display;
+
+pub use aoc_common_derive::FromCaptures;
+use regex::{bytes, Captures, Regex};
+
+use crate::patterns::{BytesPattern, Pattern, PatternError};
+
+/// Built from the named groups of a `str` match.
+pub trait FromCaptures: Sized {
+        /// `pattern` is the one that produced `caps`; it names the pattern in errors.
+        fn from_captures(pattern: &Pattern, caps: &Captures<'_>) -> Result<Self, PatternError>;
+}
+
+/// Built from the named groups of a `bytes` match.
+/// (Derive with `#[captures(bytes)]`; fields convert with `TryFrom<&[u8]>`.)
+pub trait FromBytesCaptures: Sized {
+        fn from_bytes_captures(pattern: &BytesPattern,
+                               caps: &bytes::Captures<'_>)
+                               -> Result<Self, PatternError>;
+}
+
+impl Pattern<Regex> {
+        /// First match, as a `T`.
+        pub fn extract<T: FromCaptures>(&self, hay: &str) -> Result<T, PatternError> {
+                T::from_captures(self, &self.captures_of(hay)?)
+        }
+
+        /// Every match, as a `T`.
+        pub fn extract_all<T: FromCaptures>(&self, hay: &str) -> Result<Vec<T>, PatternError> {
+                self.get()
+                    .captures_iter(hay)
+                    .map(|caps| T::from_captures(self, &caps))
+                    .collect()
+        }
+}
+
+impl Pattern<bytes::Regex> {
+        /// First match, as a `T`.
+        pub fn extract<T: FromBytesCaptures>(&self, hay: &[u8]) -> Result<T, PatternError> {
+                T::from_bytes_captures(self, &self.captures_of(hay)?)
+        }
+
+        /// Every match, as a `T`.
+        pub fn extract_all<T: FromBytesCaptures>(&self,
+                                                 hay: &[u8])
+                                                 -> Result<Vec<T>, PatternError> {
+                self.get()
+                    .captures_iter(hay)
+                    .map(|caps| T::from_bytes_captures(self, &caps))
+                    .collect()
+        }
+
+        /// Convert a named group, with the group's span attached to any error.
+        /// (Spans index into the lossy UTF-8 of the whole match.)
+        pub fn parse_group<T>(&self,
+                              caps: &bytes::Captures,
+                              group: &str)
+                              -> Result<T, PatternError>
+                where T: for<'a> TryFrom<&'a [u8]>,
+                      for<'a> <T as TryFrom<&'a [u8]>>::Error: Display
+        {
+                let m = caps.name(group)
+                            .ok_or_else(|| PatternError::MissingGroup { name:  self.name(),
+                                                                        group: group.to_string(), })?;
+                let whole = caps.get(0)
+                                .expect("group 0 always participates");
+                // lossy UTF-8 can be longer than the bytes it replaces,
+                // so convert either side of the group apart and span the group's own text
+                let (start, end) = (m.start() - whole.start(), m.end() - whole.start());
+                let bytes = whole.as_bytes();
+                let [before, text, after] = [&bytes[..start], &bytes[start..end], &bytes[end..]]
+                        .map(String::from_utf8_lossy);
+                let span = (before.len(), text.len());
+                let hay = [before, text, after].concat();
+                T::try_from(m.as_bytes()).map_err(|e| {
+                                                 PatternError::parse(self.name(),
+                                                                     group,
+                                                                     &hay,
+                                                                     span,
+                                                                     e)
+                                         })
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use super::*;
+        use crate::{bytes_patterns, patterns};
+
+        patterns! {
+                RE_MOVE = r"(?<dir>[UDLR]) (?<steps>\d+)(?: \((?<color>#\w+)\))?";
+        }
+        bytes_patterns! {
+                RE_NODE = r"(?<input>\w{3}) = \((?<left>\w{3}), (?<right>\w{3})\)";
+                RE_TAGGED = r"(?-u)(?<tag>[^ ]+) (?<id>\w+)";
+        }
+
+        #[derive(FromCaptures, Debug, PartialEq)]
+        #[captures(pattern = RE_MOVE)]
+        struct Move {
+                dir:   char,
+                #[captures(group = "steps")]
+                count: u32,
+                color: Option<String>,
+        }
+
+        #[derive(FromCaptures, Debug, PartialEq)]
+        #[captures(pattern = RE_NODE, bytes)]
+        struct Node {
+                input: [u8; 3],
+                left:  [u8; 3],
+                right: [u8; 3],
+        }
+
+        #[test]
+        fn test_derive_str() -> Result<(), PatternError> {
+                assert_eq!("R 6 (#70c710)".parse::<Move>()?,
+                           Move { dir:   'R',
+                                  count: 6,
+                                  color: Some("#70c710".to_string()), });
+                assert_eq!("U 2".parse::<Move>()?.color, None);
+                assert_eq!(RE_MOVE.extract_all::<Move>("L 1, D 22")?
+                                  .len(),
+                           2);
+                Ok(())
+        }
+
+        #[test]
+        fn test_derive_errors_carry_spans() {
+                let Err(PatternError::Parse(err)) = "D 99999999999".parse::<Move>()
+                else {
+                        panic!("expected a parse error");
+                };
+                assert_eq!((err.group.as_str(), err.value.as_str()),
+                           ("steps", "99999999999"));
+                assert_eq!((err.span.offset(), err.span.len()), (2, 11));
+                assert!(matches!("X 1".parse::<Move>(), Err(PatternError::NoMatch { .. })));
+        }
+
+        #[test]
+        fn test_derive_bytes() -> Result<(), PatternError> {
+                let node = Node::try_from(&b"AAA = (BBB, CCC)"[..])?;
+                assert_eq!(node, Node { input: *b"AAA",
+                                        left:  *b"BBB",
+                                        right: *b"CCC", });
+                Ok(())
+        }
+
+        #[test]
+        fn test_bytes_span_in_lossy_text() {
+                // each bad byte reads as a 3-byte U+FFFD, pushing `id` from 3 to 7
+                let caps = RE_TAGGED.captures_of(b"\xff\xfe AB")
+                                    .unwrap();
+                let Err(PatternError::Parse(err)) = RE_TAGGED.parse_group::<[u8; 3]>(&caps, "id")
+                else {
+                        panic!("expected a parse error");
+                };
+                assert_eq!((err.value.as_str(), err.span.offset(), err.span.len()),
+                           ("AB", 7, 2));
+        }
+}
@@ -0,0 +1,527 @@
+//! Dense, row-major 2D grids for character-grid puzzles.
+//!
+//! Positions are unsigned and every neighbor lookup is bounds-checked,
+//! so edge cells simply have fewer neighbors; no padding, no `(i64, i64)` arithmetic
+//! that can wander off the grid.
+//!
+//! ## e.g.
+//! ```
+//! use aoc_common::grid::{Grid, Pos};
+//!
+//! let grid: Grid<u8> = "467..\n...*.\n..35.".parse().unwrap();
+//! assert_eq!((grid.height(), grid.width()), (3, 5));
+//! assert_eq!(grid[Pos::new(1, 3)], b'*');
+//!
+//! // corners only have 3 neighbors
+//! assert_eq!(grid.neighbors8(Pos::new(0, 0)).count(), 3);
+//!
+//! let numbers: Vec<_> = grid.runs(u8::is_ascii_digit).collect();
+//! assert_eq!(numbers.len(), 2);
+//! assert_eq!(numbers[1].start(), Pos::new(2, 2));
+//! ```
+
+use std::{
+        fmt,
+        ops::{Index, IndexMut},
+        str::FromStr,
+};
+
+use miette::Diagnostic;
+use thiserror::Error;
+
+/// Errors from building a grid.
+#[derive(Error, Diagnostic, Debug, PartialEq, Eq)]
+pub enum GridError {
+        #[error("row {row} has {found} cells, expected {expected}")]
+        #[diagnostic(code(aoc::grid::ragged),
+                     help("every row of a grid must be the same width"))]
+        Ragged {
+                row:      usize,
+                expected: usize,
+                found:    usize,
+        },
+}
+
+/// Offsets to the 4 orthogonal neighbors: up, left, right, down.
+const DELTAS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+/// Offsets to all 8 surrounding neighbors, in row-major order.
+const DELTAS_8: [(isize, isize); 8] = [(-1, -1),
+                                       (-1, 0),
+                                       (-1, 1),
+                                       (0, -1),
+                                       (0, 1),
+                                       (1, -1),
+                                       (1, 0),
+                                       (1, 1)];
+
+/// A cell position: row, then column, from the top-left.
+/// Ordering is row-major.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Pos {
+        pub row: usize,
+        pub col: usize,
+}
+
+impl Pos {
+        pub const fn new(row: usize, col: usize) -> Self {
+                Self { row, col }
+        }
+
+        /// Position shifted by a signed offset; `None` if that goes negative.
+        /// (Upper bounds are the grid's business.)
+        pub fn offset(self, d_row: isize, d_col: isize) -> Option<Self> {
+                Some(Self { row: self.row
+                                     .checked_add_signed(d_row)?,
+                            col: self.col
+                                     .checked_add_signed(d_col)?, })
+        }
+}
+
+impl fmt::Display for Pos {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "({}, {})", self.row, self.col)
+        }
+}
+
+impl From<(usize, usize)> for Pos {
+        fn from((row, col): (usize, usize)) -> Self {
+                Self { row, col }
+        }
+}
+
+/// Dense grid, stored row-major.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+        width:  usize,
+        height: usize,
+        cells:  Vec<T>,
+}
+
+impl<T> Grid<T> {
+        /// Grid of `height` rows and `width` columns, every cell `fill`.
+        pub fn new(height: usize, width: usize, fill: T) -> Self
+                where T: Clone {
+                Self { width,
+                       height,
+                       cells: vec![fill; width * height] }
+        }
+
+        /// Grid from rows, which must all be the same width.
+        pub fn from_rows<R>(rows: impl IntoIterator<Item = R>) -> Result<Self, GridError>
+                where R: IntoIterator<Item = T> {
+                let mut cells = Vec::new();
+                let mut width = None;
+                let mut height = 0;
+                for (row, cells_in_row) in rows.into_iter().enumerate() {
+                        let before = cells.len();
+                        cells.extend(cells_in_row);
+                        let found = cells.len() - before;
+                        match width {
+                                None => width = Some(found),
+                                Some(expected) if expected != found => {
+                                        return Err(GridError::Ragged { row,
+                                                                       expected,
+                                                                       found });
+                                },
+                                Some(_) => {},
+                        }
+                        height += 1;
+                }
+                Ok(Self { width: width.unwrap_or(0),
+                          height,
+                          cells })
+        }
+
+        /// Grid from text, one row per (non-empty) line, each `char` mapped by `f`.
+        pub fn parse_with(input: &str, mut f: impl FnMut(char) -> T) -> Result<Self, GridError> {
+                Self::from_rows(input.lines()
+                                     .filter(|line| !line.is_empty())
+                                     .map(|line| {
+                                             line.chars()
+                                                 .map(&mut f)
+                                                 .collect::<Vec<_>>()
+                                     }))
+        }
+
+        pub fn width(&self) -> usize {
+                self.width
+        }
+
+        pub fn height(&self) -> usize {
+                self.height
+        }
+
+        pub fn contains(&self, pos: Pos) -> bool {
+                pos.row < self.height && pos.col < self.width
+        }
+
+        fn idx(&self, pos: Pos) -> usize {
+                pos.row * self.width + pos.col
+        }
+
+        pub fn get(&self, pos: Pos) -> Option<&T> {
+                self.contains(pos)
+                    .then(|| &self.cells[self.idx(pos)])
+        }
+
+        pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+                if self.contains(pos) {
+                        let idx = self.idx(pos);
+                        Some(&mut self.cells[idx])
+                }
+                else {
+                        None
+                }
+        }
+
+        /// Every position, row-major.
+        pub fn positions(&self) -> impl Iterator<Item = Pos> {
+                let width = self.width;
+                (0..self.height).flat_map(move |row| (0..width).map(move |col| Pos::new(row, col)))
+        }
+
+        /// Every cell with its position, row-major.
+        pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+                self.positions()
+                    .zip(&self.cells)
+        }
+
+        /// `pos` shifted by an offset, if that's still on the grid.
+        pub fn step(&self, pos: Pos, d_row: isize, d_col: isize) -> Option<Pos> {
+                pos.offset(d_row, d_col)
+                   .filter(|&p| self.contains(p))
+        }
+
+        /// Orthogonal neighbors on the grid.
+        pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+                DELTAS_4.iter()
+                        .filter_map(move |&(dr, dc)| self.step(pos, dr, dc))
+        }
+
+        /// Orthogonal and diagonal neighbors on the grid.
+        pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+                DELTAS_8.iter()
+                        .filter_map(move |&(dr, dc)| self.step(pos, dr, dc))
+        }
+
+        /// One row's cells. Panics if `row` is out of bounds.
+        pub fn row(&self, row: usize) -> &[T] {
+                assert!(row < self.height,
+                        "row {} out of bounds ({} rows)",
+                        row,
+                        self.height);
+                &self.cells[row * self.width..(row + 1) * self.width]
+        }
+
+        pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+                // `chunks` would panic on a zero width
+                (0..self.height).map(|row| self.row(row))
+        }
+
+        /// One column's cells, top to bottom. Panics if `col` is out of bounds.
+        pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+                assert!(col < self.width,
+                        "column {} out of bounds ({} columns)",
+                        col,
+                        self.width);
+                self.cells
+                    .iter()
+                    .skip(col)
+                    .step_by(self.width)
+        }
+
+        pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+                (0..self.width).map(|col| self.column(col))
+        }
+
+        /// Every `height` x `width` sub-grid that fits, by top-left corner, row-major.
+        pub fn windows(&self, height: usize, width: usize) -> impl Iterator<Item = Window<'_, T>> {
+                let rows = (self.height + 1).saturating_sub(height);
+                let cols = (self.width + 1).saturating_sub(width);
+                // a zero-sized window would "fit" everywhere, including off the end
+                let (rows, cols) = if height == 0 || width == 0 {
+                        (0, 0)
+                }
+                else {
+                        (rows, cols)
+                };
+                (0..rows).flat_map(move |row| {
+                                 (0..cols).map(move |col| Window { grid: self,
+                                                                   origin: Pos::new(row, col),
+                                                                   height,
+                                                                   width })
+                         })
+        }
+
+        /// Maximal horizontal runs of cells matching `pred`, row-major.
+        /// Runs never wrap from one row to the next.
+        pub fn runs<F: Fn(&T) -> bool>(&self, pred: F) -> Runs<'_, T, F> {
+                Runs { grid: self,
+                       pred,
+                       pos: Pos::default() }
+        }
+
+        /// Cells of a run.
+        pub fn run_cells(&self, run: Run) -> &[T] {
+                &self.row(run.start.row)[run.start.col..run.start.col + run.len]
+        }
+
+        /// Positions touching a run (diagonals included), on the grid, excluding the run itself.
+        pub fn run_neighbors(&self, run: Run) -> impl Iterator<Item = Pos> + '_ {
+                let Pos { row, col } = run.start;
+                let (first_row, first_col) = (row.saturating_sub(1), col.saturating_sub(1));
+                let last_row = (row + 1).min(self.height.saturating_sub(1));
+                let last_col = (col + run.len).min(self.width.saturating_sub(1));
+                (first_row..=last_row).flat_map(move |r| {
+                                              (first_col..=last_col).map(move |c| Pos::new(r, c))
+                                      })
+                                      .filter(move |&p| !run.contains(p))
+        }
+
+        /// Same shape, each cell mapped.
+        pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+                Grid { width:  self.width,
+                       height: self.height,
+                       cells:  self.cells
+                                   .iter()
+                                   .map(f)
+                                   .collect(), }
+        }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+        type Output = T;
+
+        /// Panics if `pos` is off the grid; use `get` for a checked lookup.
+        fn index(&self, pos: Pos) -> &T {
+                self.get(pos)
+                    .unwrap_or_else(|| {
+                            panic!("{} is off a {}x{} grid", pos, self.height, self.width)
+                    })
+        }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+        fn index_mut(&mut self, pos: Pos) -> &mut T {
+                let (height, width) = (self.height, self.width);
+                self.get_mut(pos)
+                    .unwrap_or_else(|| panic!("{} is off a {}x{} grid", pos, height, width))
+        }
+}
+
+impl FromStr for Grid<u8> {
+        type Err = GridError;
+
+        /// One byte per cell. (Puzzle grids are ASCII.)
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::from_rows(s.lines()
+                                 .filter(|line| !line.is_empty())
+                                 .map(|line| line.bytes()))
+        }
+}
+
+impl FromStr for Grid<char> {
+        type Err = GridError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::parse_with(s, |c| c)
+        }
+}
+
+/// Rows of ASCII, as parsed.
+impl fmt::Display for Grid<u8> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for row in self.rows() {
+                        writeln!(f, "{}", String::from_utf8_lossy(row))?;
+                }
+                Ok(())
+        }
+}
+
+impl fmt::Display for Grid<char> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for row in self.rows() {
+                        writeln!(f, "{}", row.iter().collect::<String>())?;
+                }
+                Ok(())
+        }
+}
+
+/// A horizontal stretch of cells within one row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Run {
+        start: Pos,
+        len:   usize,
+}
+
+impl Run {
+        pub fn new(start: Pos, len: usize) -> Self {
+                Self { start, len }
+        }
+
+        pub fn start(&self) -> Pos {
+                self.start
+        }
+
+        pub fn len(&self) -> usize {
+                self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+                self.len == 0
+        }
+
+        pub fn contains(&self, pos: Pos) -> bool {
+                pos.row == self.start.row
+                && (self.start.col..self.start.col + self.len).contains(&pos.col)
+        }
+
+        /// Each position the run covers, left to right.
+        pub fn positions(&self) -> impl Iterator<Item = Pos> {
+                let Pos { row, col } = self.start;
+                (col..col + self.len).map(move |c| Pos::new(row, c))
+        }
+}
+
+/// Iterator over the runs of a grid. (See [`Grid::runs`].)
+pub struct Runs<'g, T, F> {
+        grid: &'g Grid<T>,
+        pred: F,
+        /// where the scan resumes
+        pos:  Pos,
+}
+
+impl<T, F: Fn(&T) -> bool> Iterator for Runs<'_, T, F> {
+        type Item = Run;
+
+        fn next(&mut self) -> Option<Run> {
+                while self.pos.row < self.grid.height {
+                        let cells = self.grid.row(self.pos.row);
+                        let mut col = self.pos.col;
+                        while col < cells.len() && !(self.pred)(&cells[col]) {
+                                col += 1;
+                        }
+                        let start = col;
+                        while col < cells.len() && (self.pred)(&cells[col]) {
+                                col += 1;
+                        }
+                        if col > start {
+                                self.pos.col = col;
+                                return Some(Run::new(Pos::new(self.pos.row, start), col - start));
+                        }
+                        self.pos = Pos::new(self.pos.row + 1, 0);
+                }
+                None
+        }
+}
+
+/// A rectangular view into a grid. Positions are relative to the window's top-left.
+#[derive(Debug, Clone, Copy)]
+pub struct Window<'g, T> {
+        grid:   &'g Grid<T>,
+        origin: Pos,
+        height: usize,
+        width:  usize,
+}
+
+impl<'g, T> Window<'g, T> {
+        /// Top-left corner, in grid coordinates.
+        pub fn origin(&self) -> Pos {
+                self.origin
+        }
+
+        pub fn get(&self, pos: Pos) -> Option<&'g T> {
+                if pos.row < self.height && pos.col < self.width {
+                        self.grid
+                            .get(Pos::new(self.origin.row + pos.row, self.origin.col + pos.col))
+                }
+                else {
+                        None
+                }
+        }
+
+        /// The window's cells, row-major.
+        pub fn cells(&self) -> impl Iterator<Item = &'g T> + '_ {
+                (0..self.height).flat_map(move |r| {
+                                        let row = self.grid
+                                                      .row(self.origin.row + r);
+                                        &row[self.origin.col..self.origin.col + self.width]
+                                })
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use super::*;
+
+        const SCHEMATIC: &str = "467..114..\n...*......\n..35..633.\n";
+
+        #[test]
+        fn test_parse_and_index() {
+                let grid: Grid<u8> = SCHEMATIC.parse().unwrap();
+                assert_eq!((grid.height(), grid.width()), (3, 10));
+                assert_eq!(grid[Pos::new(1, 3)], b'*');
+                assert_eq!(grid.get(Pos::new(3, 0)), None);
+                assert_eq!(grid.get(Pos::new(0, 10)), None);
+                assert_eq!(grid.to_string().len(), SCHEMATIC.len());
+
+                assert_eq!("ab\nc".parse::<Grid<char>>(),
+                           Err(GridError::Ragged { row:      1,
+                                                   expected: 2,
+                                                   found:    1, }));
+        }
+
+        #[test]
+        fn test_neighbors_are_bounds_checked() {
+                let grid = Grid::new(3, 4, 0u8);
+                assert_eq!(grid.neighbors8(Pos::new(0, 0))
+                               .collect::<Vec<_>>(),
+                           vec![Pos::new(0, 1), Pos::new(1, 0), Pos::new(1, 1)]);
+                assert_eq!(grid.neighbors8(Pos::new(1, 1))
+                               .count(),
+                           8);
+                assert_eq!(grid.neighbors4(Pos::new(2, 3))
+                               .collect::<Vec<_>>(),
+                           vec![Pos::new(1, 3), Pos::new(2, 2)]);
+                assert_eq!(grid.step(Pos::new(0, 0), -1, 0), None);
+        }
+
+        #[test]
+        fn test_rows_columns_windows() {
+                let grid = Grid::from_rows([[1, 2, 3], [4, 5, 6]]).unwrap();
+                assert_eq!(grid.rows()
+                               .collect::<Vec<_>>(),
+                           vec![&[1, 2, 3][..], &[4, 5, 6][..]]);
+                assert_eq!(grid.column(1)
+                               .copied()
+                               .collect::<Vec<_>>(),
+                           vec![2, 5]);
+                assert_eq!(grid.columns().count(), 3);
+
+                let sums: Vec<i32> = grid.windows(2, 2)
+                                         .map(|w| w.cells().sum())
+                                         .collect();
+                assert_eq!(sums, vec![12, 16]);
+                assert_eq!(grid.windows(3, 1).count(), 0);
+        }
+
+        #[test]
+        fn test_runs_and_run_neighbors() {
+                let grid: Grid<u8> = SCHEMATIC.parse().unwrap();
+                let runs: Vec<Run> = grid.runs(u8::is_ascii_digit)
+                                         .collect();
+                assert_eq!(runs.len(), 4);
+                assert_eq!(grid.run_cells(runs[0]), b"467");
+                assert_eq!(runs[3], Run::new(Pos::new(2, 6), 3));
+
+                // "467" at the top-left corner: 3 below, 1 to the right, 1 diagonal
+                assert_eq!(grid.run_neighbors(runs[0])
+                               .count(),
+                           5);
+                assert!(grid.run_neighbors(runs[0])
+                            .any(|p| grid[p] == b'*'));
+                assert_eq!(runs[0].positions()
+                                  .collect::<Vec<_>>(),
+                           vec![Pos::new(0, 0), Pos::new(0, 1), Pos::new(0, 2)]);
+        }
+}
@@ -0,0 +1,13 @@
+//! Shared library code for Advent of Code 2023.
+//! Home for the pieces that had been copy-pasted across days.
+
+// lets derive output (which names `::aoc_common`) also work inside this crate
+extern crate self as aoc_common;
+
+pub mod captures;
+pub mod grid;
+pub mod patterns;
+pub mod spanned;
+
+// re-exported so derive output resolves without each day naming `regex` itself
+pub use regex;
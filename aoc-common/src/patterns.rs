@@ -0,0 +1,420 @@
+//! Shared registry of named regex patterns.
+//!
+//! Replaces the per-day `regex_lazyonce!` macro copies and `Lazy<Regex>` statics.
+//! Each pattern is a `static` with a name, compiled on first use (or ahead of use via
+//! [`precompile_all`]), and timed when it compiles so we can see what regex setup costs us.
+//!
+//! ## e.g.
+//! ```
+//! use aoc_common::{patterns, patterns::parse_group};
+//!
+//! patterns! {
+//!         RE_GAME = r"Game (?<id>\d+):";
+//! }
+//!
+//! let caps = RE_GAME.captures_of("Game 12: 3 blue").unwrap();
+//! assert_eq!(parse_group::<u64>(&caps, "id").unwrap(), 12);
+//! assert!(RE_GAME.captures_of("Gmae 12:").is_err());
+//! ```
+//!
+//! # PERF: `regex-automata` would let us serialize DFAs and roll them into the binary.
+//!  Until then "ahead-of-time" here means compiling eagerly, before the timed work starts.
+
+use std::{
+        fmt::Display,
+        ops::Deref,
+        str::FromStr,
+        sync::Mutex,
+        time::{Duration, Instant},
+};
+
+use miette::{Diagnostic, SourceSpan};
+use once_cell::sync::{Lazy, OnceCell};
+use regex::{bytes, Captures, Regex};
+use thiserror::Error;
+use tracing::debug;
+
+/// Declare named `str` patterns as statics.
+/// Each static's identifier doubles as the pattern's name in errors and metrics.
+#[macro_export]
+macro_rules! patterns {
+        ($($vis:vis $name:ident = $src:expr;)+) => {
+                $(
+                        $vis static $name: $crate::patterns::Pattern =
+                                $crate::patterns::Pattern::new(stringify!($name), $src);
+                )+
+        };
+}
+
+/// Declare named `bytes` patterns as statics.
+/// (As with `patterns!`, but matching against `&[u8]`.)
+#[macro_export]
+macro_rules! bytes_patterns {
+        ($($vis:vis $name:ident = $src:expr;)+) => {
+                $(
+                        $vis static $name: $crate::patterns::BytesPattern =
+                                $crate::patterns::Pattern::new(stringify!($name), $src);
+                )+
+        };
+}
+
+/// Errors from compiling patterns or pulling values out of their captures.
+#[derive(Error, Diagnostic, Debug)]
+pub enum PatternError {
+        #[error("pattern `{name}` failed to compile")]
+        #[diagnostic(code(aoc::pattern::compile))]
+        Compile {
+                name:   &'static str,
+                #[source]
+                source: regex::Error,
+        },
+
+        #[error("pattern `{name}` did not match {hay:?}")]
+        #[diagnostic(code(aoc::pattern::no_match))]
+        NoMatch { name: &'static str, hay: String },
+
+        #[error("pattern `{name}` matched, but group `{group}` did not participate")]
+        #[diagnostic(code(aoc::pattern::missing_group))]
+        MissingGroup { name: &'static str, group: String },
+
+        #[error(transparent)]
+        #[diagnostic(transparent)]
+        Parse(Box<GroupParseError>),
+}
+
+/// A capture group matched, but its text wouldn't parse into the wanted type.
+/// Carries the matched text as source, with the group's span labelled.
+#[derive(Error, Diagnostic, Debug)]
+#[error("group `{group}` of pattern `{name}`: could not parse {value:?}")]
+#[diagnostic(code(aoc::pattern::parse))]
+pub struct GroupParseError {
+        pub name:   &'static str,
+        pub group:  String,
+        pub value:  String,
+        #[source_code]
+        pub hay:    String,
+        #[label("{reason}")]
+        pub span:   SourceSpan,
+        pub reason: String,
+}
+
+impl PatternError {
+        /// Build a boxed `Parse` error.
+        pub fn parse(name: &'static str,
+                     group: &str,
+                     hay: &str,
+                     span: (usize, usize),
+                     reason: impl Display)
+                     -> Self {
+                let value = hay.get(span.0..span.0 + span.1)
+                               .unwrap_or_default()
+                               .to_string();
+                Self::Parse(Box::new(GroupParseError { name,
+                                                       group: group.to_string(),
+                                                       value,
+                                                       hay: hay.to_string(),
+                                                       span: span.into(),
+                                                       reason: reason.to_string() }))
+        }
+}
+
+/// Regex engines a `Pattern` can hold.
+pub trait Engine: Sized + Send + Sync + 'static {
+        /// Label used in metrics.
+        const KIND: &'static str;
+
+        fn compile(src: &str) -> Result<Self, regex::Error>;
+}
+
+impl Engine for Regex {
+        const KIND: &'static str = "str";
+
+        fn compile(src: &str) -> Result<Self, regex::Error> {
+                Regex::new(src)
+        }
+}
+
+impl Engine for bytes::Regex {
+        const KIND: &'static str = "bytes";
+
+        fn compile(src: &str) -> Result<Self, regex::Error> {
+                bytes::Regex::new(src)
+        }
+}
+
+/// A named regex pattern; compiled once, on first use.
+/// Derefs to the compiled regex, so it reads like the `Lazy<Regex>` statics it replaces.
+pub struct Pattern<R: Engine = Regex> {
+        name:         &'static str,
+        source:       &'static str,
+        compiled:     OnceCell<R>,
+        compile_time: OnceCell<Duration>,
+}
+
+/// A named pattern matching against bytes.
+pub type BytesPattern = Pattern<bytes::Regex>;
+
+/// Compile timing for a single pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternMetrics {
+        pub name:         &'static str,
+        pub kind:         &'static str,
+        pub source_len:   usize,
+        pub compile_time: Duration,
+}
+
+/// Timings for every pattern compiled so far in this process, in compile order.
+static METRICS: Lazy<Mutex<Vec<PatternMetrics>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Timings for every pattern compiled so far, in compile order.
+pub fn metrics() -> Vec<PatternMetrics> {
+        METRICS.lock()
+               .expect("pattern metrics poisoned")
+               .clone()
+}
+
+/// Anything that can be compiled ahead of use.
+/// (Object safe, so patterns of both engines can share a list.)
+pub trait Precompile: Sync {
+        fn name(&self) -> &'static str;
+
+        /// Compile now (if not already), returning how long compilation took.
+        fn precompile(&self) -> Result<Duration, PatternError>;
+}
+
+impl<R: Engine> Precompile for Pattern<R> {
+        fn name(&self) -> &'static str {
+                self.name
+        }
+
+        fn precompile(&self) -> Result<Duration, PatternError> {
+                self.try_get()?;
+                Ok(*self.compile_time
+                        .get()
+                        .expect("compile time is set alongside the regex"))
+        }
+}
+
+/// Compile a set of patterns up front (e.g. before benchmarking the real work).
+pub fn precompile_all(patterns: &[&dyn Precompile]) -> Result<Duration, PatternError> {
+        patterns.iter()
+                .map(|p| p.precompile())
+                .sum()
+}
+
+impl<R: Engine> Pattern<R> {
+        pub const fn new(name: &'static str, source: &'static str) -> Self {
+                Self { name,
+                       source,
+                       compiled: OnceCell::new(),
+                       compile_time: OnceCell::new() }
+        }
+
+        pub fn name(&self) -> &'static str {
+                self.name
+        }
+
+        pub fn source(&self) -> &'static str {
+                self.source
+        }
+
+        /// How long this pattern took to compile, if it has been.
+        pub fn compile_time(&self) -> Option<Duration> {
+                self.compile_time
+                    .get()
+                    .copied()
+        }
+
+        /// The compiled regex, compiling it if needed.
+        pub fn try_get(&self) -> Result<&R, PatternError> {
+                self.compiled
+                    .get_or_try_init(|| {
+                            let start = Instant::now();
+                            let re = R::compile(self.source).map_err(|source| {
+                                                                    PatternError::Compile { name: self.name,
+                                                                                            source }
+                                                            })?;
+                            let elapsed = start.elapsed();
+                            let _ = self.compile_time.set(elapsed);
+                            debug!(name = self.name, ?elapsed, "compiled pattern");
+                            METRICS.lock()
+                                   .expect("pattern metrics poisoned")
+                                   .push(PatternMetrics { name:         self.name,
+                                                          kind:         R::KIND,
+                                                          source_len:   self.source.len(),
+                                                          compile_time: elapsed, });
+                            Ok(re)
+                    })
+        }
+
+        /// The compiled regex.
+        ///
+        /// # Panics
+        /// If the pattern source is invalid.
+        /// Patterns are literals in our own code, so that's a bug, not bad input.
+        pub fn get(&self) -> &R {
+                match self.try_get() {
+                        Ok(re) => re,
+                        Err(e) => panic!("{}: {:?}", e, self.source),
+                }
+        }
+}
+
+impl<R: Engine> Deref for Pattern<R> {
+        type Target = R;
+
+        fn deref(&self) -> &R {
+                self.get()
+        }
+}
+
+impl Pattern<Regex> {
+        /// Captures of the first match, or an error naming the pattern.
+        pub fn captures_of<'h>(&self, hay: &'h str) -> Result<Captures<'h>, PatternError> {
+                self.get()
+                    .captures(hay)
+                    .ok_or_else(|| PatternError::NoMatch { name: self.name,
+                                                           hay:  hay.to_string(), })
+        }
+
+        /// Parse every (whole) match in the haystack.
+        /// e.g. `RE_NUM.parse_all::<u64>("1 2 3")`
+        pub fn parse_all<T>(&self, hay: &str) -> Result<Vec<T>, PatternError>
+                where T: FromStr,
+                      T::Err: Display {
+                self.get()
+                    .find_iter(hay)
+                    .map(|m| {
+                            m.as_str()
+                             .parse::<T>()
+                             .map_err(|e| {
+                                     PatternError::parse(self.name,
+                                                         "0",
+                                                         hay,
+                                                         (m.start(), m.len()),
+                                                         e)
+                             })
+                    })
+                    .collect()
+        }
+
+        /// Text of a named group.
+        pub fn group<'h>(&self, caps: &Captures<'h>, group: &str) -> Result<&'h str, PatternError> {
+                caps.name(group)
+                    .map(|m| m.as_str())
+                    .ok_or_else(|| PatternError::MissingGroup { name:  self.name,
+                                                                group: group.to_string(), })
+        }
+
+        /// Parse a named group, with the group's span attached to any error.
+        pub fn parse_group<T>(&self, caps: &Captures, group: &str) -> Result<T, PatternError>
+                where T: FromStr,
+                      T::Err: Display {
+                let m = caps.name(group)
+                            .ok_or_else(|| PatternError::MissingGroup { name:  self.name,
+                                                                        group: group.to_string(), })?;
+                m.as_str()
+                 .parse::<T>()
+                 .map_err(|e| {
+                         // spans are relative to the whole match, which is all we keep as source
+                         let whole = caps.get(0)
+                                         .expect("group 0 always participates");
+                         PatternError::parse(self.name,
+                                             group,
+                                             whole.as_str(),
+                                             (m.start() - whole.start(), m.len()),
+                                             e)
+                 })
+        }
+}
+
+impl Pattern<bytes::Regex> {
+        /// Captures of the first match, or an error naming the pattern.
+        pub fn captures_of<'h>(&self, hay: &'h [u8]) -> Result<bytes::Captures<'h>, PatternError> {
+                self.get()
+                    .captures(hay)
+                    .ok_or_else(|| PatternError::NoMatch { name: self.name,
+                                                           hay:  String::from_utf8_lossy(hay).to_string(), })
+        }
+
+        /// Bytes of a named group.
+        pub fn group<'h>(&self,
+                         caps: &bytes::Captures<'h>,
+                         group: &str)
+                         -> Result<&'h [u8], PatternError> {
+                caps.name(group)
+                    .map(|m| m.as_bytes())
+                    .ok_or_else(|| PatternError::MissingGroup { name:  self.name,
+                                                                group: group.to_string(), })
+        }
+}
+
+/// Parse a named group of any `str` captures.
+/// (Free-function form, for when the `Pattern` isn't at hand; errors name the group only.)
+pub fn parse_group<T>(caps: &Captures, group: &str) -> Result<T, PatternError>
+        where T: FromStr,
+              T::Err: Display {
+        static ANON: Pattern = Pattern::new("<captures>", "");
+        ANON.parse_group(caps, group)
+}
+
+#[cfg(test)]
+mod tests {
+        use super::*;
+
+        patterns! {
+                RE_PAIR = r"(?<key>\w+)=(?<val>\w+)";
+                RE_NUM = r"\d+";
+                RE_BROKEN = r"(unclosed";
+        }
+        bytes_patterns! {
+                RE_NODE = r"(?<node>[A-Z]{3}) = ";
+        }
+
+        #[test]
+        fn test_named_and_deref() {
+                assert_eq!(RE_NUM.name(), "RE_NUM");
+                assert!(RE_NUM.is_match("abc 12"));
+                assert!(RE_NUM.compile_time()
+                              .is_some());
+        }
+
+        #[test]
+        fn test_typed_captures() -> Result<(), PatternError> {
+                let caps = RE_PAIR.captures_of("  width=42")?;
+                assert_eq!(RE_PAIR.group(&caps, "key")?, "width");
+                assert_eq!(RE_PAIR.parse_group::<u32>(&caps, "val")?, 42);
+                assert_eq!(RE_NUM.parse_all::<u8>("1, 2, 3")?, vec![1, 2, 3]);
+                Ok(())
+        }
+
+        #[test]
+        fn test_errors_not_panics() {
+                assert!(matches!(RE_PAIR.captures_of("no pairs here"),
+                                 Err(PatternError::NoMatch { .. })));
+
+                let caps = RE_PAIR.captures_of("x=abc")
+                                  .unwrap();
+                let Err(PatternError::Parse(err)) = RE_PAIR.parse_group::<u32>(&caps, "val")
+                else {
+                        panic!("expected a parse error");
+                };
+                assert_eq!(err.value, "abc");
+                assert_eq!((err.span.offset(), err.span.len()), (2, 3));
+
+                assert!(matches!(RE_PAIR.group(&caps, "nope"),
+                                 Err(PatternError::MissingGroup { .. })));
+                assert!(matches!(RE_NUM.parse_all::<u8>("300"), Err(PatternError::Parse(_))));
+                assert!(matches!(RE_BROKEN.try_get(), Err(PatternError::Compile { .. })));
+        }
+
+        #[test]
+        fn test_bytes_and_metrics() -> Result<(), PatternError> {
+                precompile_all(&[&RE_NODE, &RE_PAIR])?;
+                let caps = RE_NODE.captures_of(b"AAA = (BBB, CCC)")?;
+                assert_eq!(RE_NODE.group(&caps, "node")?, b"AAA");
+                assert!(metrics().iter()
+                                 .any(|m| m.name == "RE_NODE" && m.kind == "bytes"));
+                Ok(())
+        }
+}
@@ -0,0 +1,208 @@
+//! Errors that point into the puzzle input.
+//!
+//! Each day says what's wrong in its own words (a `problem` type with a `Display`);
+//! `InvalidInput` carries that, labelled where it happens, for miette to draw.
+//!
+//! Only the labelled lines (and one either side, for context) are kept, as an `Excerpt`,
+//! so an error in a big input doesn't hold a copy of all of it.
+//! Spans, and line numbers as drawn, are still those of the whole input.
+//!
+//! ## e.g.
+//! ```
+//! use aoc_common::spanned::InvalidInput;
+//!
+//! let input = "Card 1: 1 | 2\nCard 9: 1 | 2\n";
+//! let err = InvalidInput::new("card", input, "expected id 2", (19, 1));
+//! assert_eq!(err.line, 2);
+//! assert_eq!(err.to_string(), "invalid card on line 2");
+//! ```
+
+use std::fmt::{Debug, Display};
+
+use miette::{Diagnostic, MietteError, MietteSpanContents, SourceCode, SourceSpan, SpanContents};
+use thiserror::Error;
+
+/// A part of the input that can't be read, and why.
+#[derive(Error, Diagnostic, Debug)]
+#[error("invalid {what} on line {line}")]
+#[diagnostic(code(aoc::invalid_input))]
+pub struct InvalidInput<P: Display + Debug> {
+        /// What the input was meant to hold, e.g. "card".
+        pub what:       &'static str,
+        /// 1-based, where `span` starts
+        pub line:       usize,
+        pub problem:    P,
+        #[source_code]
+        pub src:        Excerpt,
+        #[label("{problem}")]
+        pub span:       SourceSpan,
+        #[label("{other_note}")]
+        pub other:      Option<SourceSpan>,
+        pub other_note: &'static str,
+}
+
+impl<P: Display + Debug> InvalidInput<P> {
+        /// `problem`, labelling `span` of `input`.
+        pub fn new(what: &'static str,
+                   input: &str,
+                   problem: P,
+                   span: impl Into<SourceSpan>)
+                   -> Self {
+                let span = span.into();
+                Self { what,
+                       line: line_of(input, span.offset()),
+                       problem,
+                       src: Excerpt::new(input, &[span]),
+                       span,
+                       other: None,
+                       other_note: "" }
+        }
+
+        /// With a second label, on a related part of the input (e.g. what this one clashes with).
+        pub fn also(self, input: &str, other: impl Into<SourceSpan>, note: &'static str) -> Self {
+                let other = other.into();
+                Self { src: Excerpt::new(input, &[self.span, other]),
+                       other: Some(other),
+                       other_note: note,
+                       ..self }
+        }
+
+        /// The same error, for a part of `input` that starts `offset` bytes in.
+        /// (For errors found in a line or chunk on its own.)
+        pub fn within(self, input: &str, offset: usize) -> Self {
+                let shift =
+                        |span: SourceSpan| SourceSpan::from((offset + span.offset(), span.len()));
+                let err = Self::new(self.what, input, self.problem, shift(self.span));
+                match self.other {
+                        Some(other) => err.also(input, shift(other), self.other_note),
+                        None => err,
+                }
+        }
+}
+
+/// 1-based line of `input` that byte `offset` is on.
+fn line_of(input: &str, offset: usize) -> usize {
+        input.as_bytes()[..offset.min(input.len())].iter()
+                                                   .filter(|&&b| b == b'\n')
+                                                   .count()
+        + 1
+}
+
+/// The lines of an input that some spans cover, and where they sit in it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Excerpt {
+        text:   String,
+        /// byte offset of `text` in the input
+        offset: usize,
+        /// 0-based line of the input `text` starts on
+        line:   usize,
+}
+
+impl Excerpt {
+        /// Every line from the one before the first span to the one after the last.
+        pub fn new(input: &str, spans: &[SourceSpan]) -> Self {
+                let clamp = |at: usize| at.min(input.len());
+                let lo = clamp(spans.iter()
+                                    .map(|s| s.offset())
+                                    .min()
+                                    .unwrap_or(0));
+                let hi = clamp(spans.iter()
+                                    .map(|s| s.offset() + s.len())
+                                    .max()
+                                    .unwrap_or(0));
+                // start of the line before `lo`'s, and end of the line after `hi`'s
+                let line_start = |at: usize| {
+                        input[..at].rfind('\n')
+                                   .map_or(0, |i| i + 1)
+                };
+                let line_end = |at: usize| {
+                        input[at..].find('\n')
+                                   .map_or(input.len(), |i| at + i + 1)
+                };
+                let start = line_start(lo);
+                let start = line_start(start.saturating_sub(1));
+                let end = line_end(hi);
+                let end = line_end(end);
+                Self { text:   input[start..end].to_string(),
+                       offset: start,
+                       line:   line_of(input, start) - 1, }
+        }
+
+        pub fn text(&self) -> &str {
+                &self.text
+        }
+
+        /// Byte offset of the excerpt in the input.
+        pub fn offset(&self) -> usize {
+                self.offset
+        }
+}
+
+impl SourceCode for Excerpt {
+        fn read_span<'a>(&'a self,
+                         span: &SourceSpan,
+                         context_lines_before: usize,
+                         context_lines_after: usize)
+                         -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+                let offset = span.offset()
+                                 .checked_sub(self.offset)
+                                 .ok_or(MietteError::OutOfBounds)?;
+                let local = self.text
+                                .read_span(&(offset, span.len()).into(),
+                                           context_lines_before,
+                                           context_lines_after)?;
+                let at = (self.offset + local.span().offset(), local.span().len());
+                Ok(Box::new(MietteSpanContents::new(local.data(),
+                                                    at.into(),
+                                                    self.line + local.line(),
+                                                    local.column(),
+                                                    local.line_count())))
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use super::*;
+
+        const INPUT: &str = "one\ntwo\nthree\nfour\nfive\nsix\n";
+
+        #[test]
+        fn test_excerpt_keeps_labelled_lines() {
+                // "four", with "three" and "five" around it
+                let err = InvalidInput::new("word", INPUT, "bad", (14, 4));
+                assert_eq!((err.line, err.src.text(), err.src.offset()),
+                           (4, "three\nfour\nfive\n", 8));
+
+                // both labels, and the lines between
+                let err = err.also(INPUT, (0, 3), "first");
+                assert_eq!(err.src.text(), "one\ntwo\nthree\nfour\nfive\n");
+
+                // a label at the very end
+                let end = InvalidInput::new("word", INPUT, "missing", (INPUT.len(), 0));
+                assert_eq!((end.line, end.src.text()), (7, "six\n"));
+        }
+
+        #[test]
+        fn test_read_span_in_input_terms() {
+                let err = InvalidInput::new("word", INPUT, "bad", (14, 4));
+                let contents = err.src
+                                  .read_span(&(14, 4).into(), 0, 0)
+                                  .unwrap();
+                assert_eq!(contents.data(), b"four");
+                assert_eq!((contents.span().offset(), contents.line()), (14, 3));
+                assert!(err.src
+                           .read_span(&(0, 3).into(), 0, 0)
+                           .is_err());
+        }
+
+        #[test]
+        fn test_within() {
+                // found in "five" alone, then placed in the input
+                let err =
+                        InvalidInput::new("word", "five", "bad", (1, 2)).also("five", (0, 1), "f")
+                                                                        .within(INPUT, 19);
+                assert_eq!((err.line, err.span, err.other),
+                           (5, (20, 2).into(), Some((19, 1).into())));
+                assert_eq!(err.src.text(), "four\nfive\nsix\n");
+        }
+}
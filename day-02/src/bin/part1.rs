@@ -1,7 +1,10 @@
 //! Part 1 of Day 02 of Advent of Code 2023.
 //! Runs `part1_lib.rs` library code against `input1.txt`
 
-use day_02::part1_lib::process;
+use day_02::{
+        cubes::{default_bag, Cubes, BAG_ENV_VAR},
+        part1_lib::process_with_bag,
+};
 use miette::{Context, Result};
 
 #[cfg(feature = "dhat-heap")]
@@ -16,8 +19,18 @@ fn main() -> Result<()> {
         #[cfg(not(feature = "dhat-heap"))]
         tracing_subscriber::fmt::init();
 
+        // bag from first CLI arg, then env var, then the puzzle's default
+        // e.g. `cargo run --bin part1 -- "12 red, 13 green, 14 blue, 2 yellow"`
+        let bag = match std::env::args().nth(1)
+                                        .or_else(|| std::env::var(BAG_ENV_VAR).ok())
+        {
+                Some(spec) => spec.parse::<Cubes>()
+                                  .context("parse bag")?,
+                None => default_bag(),
+        };
+
         let file = include_str!("../../input1.txt");
-        let result = process(file).context("process part 1")?;
+        let result = process_with_bag(file, &bag).context("process part 1")?;
         println!("\n************\nSolution is:\n{}", result);
         Ok(())
 }
@@ -0,0 +1,108 @@
+//! Query CLI for Day 02 of Advent of Code 2023.
+//! Runs `query.rs` library code against `input1.txt` (or `--input <path>`).
+//!
+//! ## Usage
+//! ```text
+//! query [--input <path>] min-bags
+//! query [--input <path>] feasible "<bag>"      e.g. "12 red, 13 green, 14 blue"
+//! query [--input <path>] smallest-bag <n>
+//! query [--input <path>] histogram
+//! query [--input <path>] estimate-bag [max-per-color]
+//! ```
+
+use day_02::{
+        cubes::Cubes,
+        inference::{estimate_bag_with, InferenceConfig},
+        query::GameQuery,
+};
+use miette::{miette, Context, IntoDiagnostic, Result};
+
+const USAGE: &str =
+        "usage: query [--input <path>] (min-bags | feasible <bag> | smallest-bag <n> | histogram | \
+         estimate-bag [max-per-color])";
+
+fn main() -> Result<()> {
+        tracing_subscriber::fmt::init();
+
+        let mut args: Vec<String> = std::env::args().skip(1)
+                                                    .collect();
+        let input = match args.iter()
+                              .position(|a| a == "--input")
+        {
+                Some(i) => {
+                        let path = args.get(i + 1)
+                                       .ok_or_else(|| miette!("--input needs a path\n{}", USAGE))?
+                                       .clone();
+                        args.drain(i..=i + 1);
+                        std::fs::read_to_string(&path).into_diagnostic()
+                                                      .wrap_err_with(|| format!("read {}", path))?
+                },
+                None => include_str!("../../input1.txt").to_string(),
+        };
+        let query = GameQuery::parse(&input).context("parse games")?;
+
+        match args.iter()
+                  .map(String::as_str)
+                  .collect::<Vec<_>>()
+                  .as_slice()
+        {
+                ["min-bags"] => {
+                        let colors = query.colors();
+                        for (id, bag) in query.min_bags() {
+                                println!("Game {}: {} (power {})",
+                                         id,
+                                         bag,
+                                         bag.power(colors.iter().copied()));
+                        }
+                },
+                ["feasible", bag] => {
+                        let bag = bag.parse::<Cubes>()
+                                     .context("parse bag")?;
+                        let ids = query.feasible_under(&bag);
+                        println!("{} feasible game(s), id sum {}",
+                                 ids.len(),
+                                 ids.iter().sum::<u64>());
+                        println!("{:?}", ids);
+                },
+                ["smallest-bag", n] => {
+                        let n = n.parse::<usize>()
+                                 .into_diagnostic()
+                                 .context("parse game count")?;
+                        match query.smallest_bag_for(n) {
+                                Some(bag) => println!("{}", bag),
+                                None => println!("only {} game(s) available", query.games().len()),
+                        }
+                },
+                ["histogram"] =>
+                        for (color, hist) in query.color_histograms() {
+                                println!("{}:", color);
+                                for (count, draws) in hist {
+                                        println!("  {:>3} | {}", count, "#".repeat(draws));
+                                }
+                        },
+                ["estimate-bag", rest @ ..] if rest.len() <= 1 => {
+                        let mut config = InferenceConfig::default();
+                        if let [max] = rest {
+                                config.max_per_color = max.parse()
+                                                          .into_diagnostic()
+                                                          .context("parse max-per-color")?;
+                        }
+                        let est = estimate_bag_with(query.games(), config).context("estimate bag")?;
+                        println!("{} (log-likelihood {:.3})", est.bag, est.log_likelihood);
+                        for (color, conf) in &est.confidence {
+                                let capped = if est.at_cap.contains(color) {
+                                        " (at cap)"
+                                }
+                                else {
+                                        ""
+                                };
+                                println!("  {:>8}: confidence {:.3}{}", color, conf, capped);
+                        }
+                        if !est.converged {
+                                println!("(did not converge in {} rounds)", config.max_rounds);
+                        }
+                },
+                _ => return Err(miette!("{}", USAGE)),
+        }
+        Ok(())
+}
@@ -1,64 +1,49 @@
 use anyhow::Result;
+use aoc_common::{patterns, patterns::metrics};
 
-/// Example helpfully provided by the `once_cell` crate's documentation.
-///
-/// Simply assigns a string literal pattern to a `Regex` generator
-/// wrapped in a lazy `once_cell` wrapper.
-/// Besides avoiding perf issues with accidental-recompilation
-/// I just find using a `static` for these patterns clear and readable for this code.
-///
-/// # PERF: `regex-automata` crate allows creating a DFA, writing it, and rolling it into binary.
-///  That woudl avoid any initialization cost when running binary, and allow speed optimization
-///  for what are very simple regexes.
-macro_rules! regex_lazyonce {
-        ($re:expr $(,)?) => {{
-                static RE: once_cell::sync::OnceCell<regex::Regex> =
-                        once_cell::sync::OnceCell::new();
-                RE.get_or_init(|| regex::Regex::new($re).unwrap())
-        }};
+patterns! {
+        ID_PAT = r"Game (\d+):";
+        RED_PAT = r"(\d+) red";
+        GREEN_PAT = r"(\d+) green";
+        BLUE_PAT = r"(\d+) blue";
 }
 
-const ID_PAT: &str = r"Game (\d+):";
-const RED_PAT: &str = r"(\d+) red";
-const GREEN_PAT: &str = r"(\d+) green";
-const BLUE_PAT: &str = r"(\d+) blue";
-
 /// REFACTOR:
 /// yuuuch at repeated code.
 /// Not sure of  a better way to do this if usign regex_macro
 /// ... I should just have defined the regex as static with lazy
 /// and then iterated over them.
 fn extract_data(hay: &str) -> Vec<u32> {
-        let id = regex_lazyonce!(ID_PAT).captures(hay)
-                                        .expect("captures iter failure")
-                                        .get(1)
-                                        .map(|v| {
-                                                v.as_str()
-                                                 .parse::<u32>()
-                                                 .expect("id parse failure")
-                                        })
-                                        .expect("iteration failure");
-        let r_sum = regex_lazyonce!(RED_PAT).captures_iter(hay)
-                                            .map(|c| {
-                                                    let (_, [val]) = c.extract();
-                                                    val.parse::<u32>()
-                                                       .expect("red parse failure")
-                                            })
-                                            .sum();
-        let g_sum = regex_lazyonce!(GREEN_PAT).captures_iter(hay)
-                                              .map(|c| {
-                                                      let (_, [val]) = c.extract();
-                                                      val.parse::<u32>()
-                                                         .expect("green parse failure")
-                                              })
-                                              .sum();
-        let b_sum = regex_lazyonce!(BLUE_PAT).captures_iter(hay)
-                                             .map(|c| {
-                                                     let (_, [val]) = c.extract();
-                                                     val.parse::<u32>()
-                                                        .expect("blue parse failure")
-                                             })
-                                             .sum();
+        let id = ID_PAT.captures(hay)
+                       .expect("captures iter failure")
+                       .get(1)
+                       .map(|v| {
+                               v.as_str()
+                                .parse::<u32>()
+                                .expect("id parse failure")
+                       })
+                       .expect("iteration failure");
+        let r_sum = RED_PAT.captures_iter(hay)
+                           .map(|c| {
+                                   let (_, [val]) = c.extract();
+                                   val.parse::<u32>()
+                                      .expect("red parse failure")
+                           })
+                           .sum();
+        let g_sum = GREEN_PAT.captures_iter(hay)
+                             .map(|c| {
+                                     let (_, [val]) = c.extract();
+                                     val.parse::<u32>()
+                                        .expect("green parse failure")
+                             })
+                             .sum();
+        let b_sum = BLUE_PAT.captures_iter(hay)
+                            .map(|c| {
+                                    let (_, [val]) = c.extract();
+                                    val.parse::<u32>()
+                                       .expect("blue parse failure")
+                            })
+                            .sum();
         vec![id, r_sum, g_sum, b_sum]
 }
 
@@ -75,5 +60,10 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green\
                 let data = extract_data(line);
                 println!("{:?}", data);
         }
+
+        for m in metrics() {
+                println!("{:>10} ({:>5}, {:>3} chars): {:?}",
+                         m.name, m.kind, m.source_len, m.compile_time);
+        }
         Ok(())
 }
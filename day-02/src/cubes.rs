@@ -0,0 +1,247 @@
+//! Color-generic cube multisets for Day 02 of Advent of Code 2023.
+//!
+//! Colors are interned names rather than fixed fields, so a game mentioning "yellow" cubes
+//! is tracked like any other color instead of silently contributing nothing.
+//! The bag to compare against is an ordinary `Cubes` value, built at runtime.
+
+use std::{collections::BTreeMap, fmt, str::FromStr, sync::RwLock};
+
+use once_cell::sync::Lazy;
+
+use crate::custom_error::AocErrorDay02;
+
+/// Environment variable consulted (after CLI args) for a bag specification.
+/// e.g. `DAY02_BAG="12 red, 13 green, 14 blue, 3 yellow"`
+pub const BAG_ENV_VAR: &str = "DAY02_BAG";
+
+/// The bag from the puzzle statement: 12 red, 13 green, 14 blue.
+pub fn default_bag() -> Cubes {
+        Cubes::from_iter([("red", 12), ("green", 13), ("blue", 14)])
+}
+
+/// Interned color names.
+/// Names are leaked once on first sight; there are only ever a handful of them.
+static COLOR_NAMES: Lazy<RwLock<Vec<&'static str>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// An interned cube color.
+/// Cheap to copy and compare; ordering is by first-seen order, not alphabetical.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Color(u32);
+
+impl Color {
+        /// Get the color for a name, registering it if it's new.
+        pub fn intern(name: &str) -> Self {
+                if let Some(idx) = COLOR_NAMES.read()
+                                              .expect("color interner poisoned")
+                                              .iter()
+                                              .position(|&n| n == name)
+                {
+                        return Self(idx as u32);
+                }
+                let mut names = COLOR_NAMES.write()
+                                           .expect("color interner poisoned");
+                // re-check: another thread may have registered it between locks
+                if let Some(idx) = names.iter()
+                                        .position(|&n| n == name)
+                {
+                        return Self(idx as u32);
+                }
+                names.push(Box::leak(name.to_string()
+                                         .into_boxed_str()));
+                Self(names.len() as u32 - 1)
+        }
+
+        /// The color's name, as first interned.
+        pub fn name(&self) -> &'static str {
+                COLOR_NAMES.read()
+                           .expect("color interner poisoned")[self.0 as usize]
+        }
+}
+
+impl fmt::Display for Color {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.name())
+        }
+}
+
+/// Multiset of cubes, keyed by color.
+/// Colors not present are treated as a count of zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cubes {
+        counts: BTreeMap<Color, u64>,
+}
+
+impl Cubes {
+        /// Empty multiset.
+        pub fn new() -> Self {
+                Self::default()
+        }
+
+        /// Count of a given color (zero if absent).
+        pub fn get(&self, color: Color) -> u64 {
+                self.counts
+                    .get(&color)
+                    .copied()
+                    .unwrap_or(0)
+        }
+
+        /// Set the count of a given color.
+        pub fn set(&mut self, color: Color, count: u64) {
+                self.counts
+                    .insert(color, count);
+        }
+
+        /// Raise the count of a color to `count` if it's currently lower.
+        pub fn raise_to(&mut self, color: Color, count: u64) {
+                let entry = self.counts
+                                .entry(color)
+                                .or_insert(0);
+                *entry = (*entry).max(count);
+        }
+
+        /// Colors explicitly present, with their counts.
+        pub fn iter(&self) -> impl Iterator<Item = (Color, u64)> + '_ {
+                self.counts
+                    .iter()
+                    .map(|(&c, &n)| (c, n))
+        }
+
+        /// Colors explicitly present.
+        pub fn colors(&self) -> impl Iterator<Item = Color> + '_ {
+                self.counts.keys().copied()
+        }
+
+        /// Color-wise maximum of two multisets.
+        /// (The smallest multiset containing both.)
+        pub fn max_union(&self, other: &Self) -> Self {
+                let mut out = self.clone();
+                for (color, count) in other.iter() {
+                        out.raise_to(color, count);
+                }
+                out
+        }
+
+        /// whether a is a ~subset (inclusive) of b
+        /// Any color of `self` missing from `other` only fits if its count is zero.
+        pub fn is_subset_of(&self, other: &Self) -> bool {
+                self.iter()
+                    .all(|(color, count)| count <= other.get(color))
+        }
+
+        /// Product of the counts of each of `colors`, any missing counting as zero.
+        /// (The puzzle's "power", generalized past red, green, and blue.)
+        /// `colors` should be every color in play, not just those present:
+        /// a game without green has power zero.
+        pub fn power(&self, colors: impl IntoIterator<Item = Color>) -> u64 {
+                colors.into_iter()
+                      .map(|c| self.get(c))
+                      .product()
+        }
+}
+
+impl<'a> FromIterator<(&'a str, u64)> for Cubes {
+        fn from_iter<I: IntoIterator<Item = (&'a str, u64)>>(iter: I) -> Self {
+                let mut cubes = Cubes::new();
+                for (name, count) in iter {
+                        cubes.set(Color::intern(name), count);
+                }
+                cubes
+        }
+}
+
+impl FromStr for Cubes {
+        type Err = AocErrorDay02;
+
+        /// Parse a comma separated list of `<count> <color>` pairs.
+        /// e.g. "12 red, 13 green, 14 blue"
+        /// Repeated colors keep the larger count.
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+                use AocErrorDay02::CubesParse;
+
+                let mut cubes = Cubes::new();
+                for entry in s.split(',')
+                              .map(str::trim)
+                              .filter(|e| !e.is_empty())
+                {
+                        let Some((count, name)) = entry.split_once(char::is_whitespace)
+                        else {
+                                return Err(CubesParse(format!("expected `<count> <color>`: {:?}",
+                                                              entry)));
+                        };
+                        let count = count.parse::<u64>()
+                                         .map_err(|e| CubesParse(format!("{:?}: {}", entry, e)))?;
+                        let name = name.trim();
+                        if name.is_empty()
+                           || !name.chars()
+                                   .all(char::is_alphabetic)
+                        {
+                                return Err(CubesParse(format!("bad color name: {:?}", entry)));
+                        }
+                        cubes.raise_to(Color::intern(name), count);
+                }
+                Ok(cubes)
+        }
+}
+
+impl fmt::Display for Cubes {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for (i, (color, count)) in self.iter().enumerate() {
+                        if i > 0 {
+                                write!(f, ", ")?;
+                        }
+                        write!(f, "{} {}", count, color)?;
+                }
+                Ok(())
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use super::*;
+
+        #[test]
+        fn test_color_interning() {
+                let a = Color::intern("magenta");
+                let b = Color::intern("magenta");
+                assert_eq!(a, b);
+                assert_eq!(a.name(), "magenta");
+                assert_ne!(a, Color::intern("cyan"));
+        }
+
+        #[test]
+        fn test_parse_and_display() -> Result<(), AocErrorDay02> {
+                let cubes: Cubes = "12 red, 13 green, 14 blue".parse()?;
+                assert_eq!(cubes, default_bag());
+                assert_eq!(cubes.to_string()
+                                .parse::<Cubes>()?,
+                           cubes);
+                assert!("12 red, green".parse::<Cubes>()
+                                       .is_err());
+                assert!("twelve red".parse::<Cubes>()
+                                    .is_err());
+                Ok(())
+        }
+
+        #[test]
+        fn test_subset_with_unknown_color() -> Result<(), AocErrorDay02> {
+                let draw: Cubes = "3 red, 2 yellow".parse()?;
+                assert!(!draw.is_subset_of(&default_bag()));
+
+                let bag: Cubes = "12 red, 13 green, 14 blue, 2 yellow".parse()?;
+                assert!(draw.is_subset_of(&bag));
+                Ok(())
+        }
+
+        #[test]
+        fn test_max_union_and_power() -> Result<(), AocErrorDay02> {
+                let a: Cubes = "4 red, 2 green".parse()?;
+                let b: Cubes = "1 red, 6 blue, 3 yellow".parse()?;
+                let union = a.max_union(&b);
+                assert_eq!(union, "4 red, 2 green, 6 blue, 3 yellow".parse()?);
+                assert_eq!(union.power(union.colors()), 4 * 2 * 6 * 3);
+                // colors in play but absent zero the power
+                assert_eq!(a.power(default_bag().colors()), 0);
+                assert_eq!(a.power(a.colors()), 4 * 2);
+                Ok(())
+        }
+}
@@ -1,5 +1,6 @@
 //! Custom Error type for Day 02 of Advent of Code 2023.
 
+use aoc_common::patterns::PatternError;
 use miette::Diagnostic;
 use thiserror::Error;
 
@@ -8,4 +9,20 @@ pub enum AocErrorDay02 {
         #[error(transparent)]
         #[diagnostic(code(aoc::io_error))]
         IoError(#[from] std::io::Error),
+
+        #[error(transparent)]
+        #[diagnostic(transparent)]
+        Pattern(#[from] PatternError),
+
+        #[error("Error parsing cubes: {0}")]
+        #[diagnostic(code(aoc::cubes_parse_error))]
+        CubesParse(String),
+
+        #[error("Error parsing game: {0}")]
+        #[diagnostic(code(aoc::game_parse_error))]
+        GameParse(String),
+
+        #[error("Bag search too large: bags of up to {total} cubes, limit is {limit}")]
+        #[diagnostic(code(aoc::bag_search_too_large), help("lower max-per-color"))]
+        BagSearchTooLarge { total: u64, limit: u64 },
 }
@@ -0,0 +1,72 @@
+//! Typed game records for Day 02 of Advent of Code 2023.
+//!
+//! A game keeps every draw, rather than just the per-color maxima,
+//! so that queries and analysis can look at the draws themselves.
+
+use std::str::FromStr;
+
+use crate::{cubes::Cubes, custom_error::AocErrorDay02};
+
+/// One game: its id and each handful of cubes drawn from the bag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+        id:    u64,
+        draws: Vec<Cubes>,
+}
+
+impl Game {
+        pub fn id(&self) -> u64 {
+                self.id
+        }
+
+        pub fn draws(&self) -> &[Cubes] {
+                &self.draws
+        }
+
+        /// Smallest bag that could have produced every draw of this game.
+        pub fn min_bag(&self) -> Cubes {
+                self.draws
+                    .iter()
+                    .fold(Cubes::new(), |acc, draw| acc.max_union(draw))
+        }
+
+        /// Whether every draw of this game fits in the given bag.
+        pub fn is_feasible_under(&self, bag: &Cubes) -> bool {
+                self.draws
+                    .iter()
+                    .all(|draw| draw.is_subset_of(bag))
+        }
+}
+
+impl FromStr for Game {
+        type Err = AocErrorDay02;
+
+        /// Parse a single line.
+        /// e.g. "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green"
+        fn from_str(line: &str) -> Result<Self, Self::Err> {
+                use AocErrorDay02::GameParse;
+
+                let Some((head, body)) = line.split_once(':')
+                else {
+                        return Err(GameParse(format!("missing `:` in {:?}", line)));
+                };
+                let id = head.trim()
+                             .strip_prefix("Game")
+                             .ok_or_else(|| GameParse(format!("missing `Game` in {:?}", line)))?
+                             .trim()
+                             .parse::<u64>()
+                             .map_err(|e| GameParse(format!("bad id in {:?}: {}", line, e)))?;
+                let draws = body.split(';')
+                                .map(str::parse::<Cubes>)
+                                .collect::<Result<Vec<_>, _>>()?;
+                Ok(Self { id, draws })
+        }
+}
+
+/// Parse every non-blank line of the input into a game.
+pub fn parse_games(input: &str) -> Result<Vec<Game>, AocErrorDay02> {
+        input.lines()
+             .filter(|line| !line.trim().is_empty())
+             .map(str::parse::<Game>)
+             .collect()
+}
@@ -0,0 +1,324 @@
+//! Estimating the shared bag behind every game of Day 02.
+//!
+//! Part 2 takes each game's minimum bag on its own.
+//! Here all games are assumed to draw from *one* bag (cubes go back between draws),
+//! and we look for the bag contents most likely to have produced every draw we saw.
+//!
+//! ## Model
+//! A draw of `n` cubes from a bag of `N` is sampling without replacement,
+//! so its probability is multivariate hypergeometric:
+//! `P(draw) = Π_c C(N_c, k_c) / C(N, n)`
+//! The log-likelihood of a bag is that summed (in log space) over every draw of every game.
+//!
+//! ## Search
+//! Coordinate ascent over integer counts: starting from the combined minimum bag,
+//! repeatedly set each color to its best count with the others held fixed, until nothing moves.
+//! Counts are capped, since the likelihood can keep creeping up toward its with-replacement
+//! limit as the bag grows; colors that end up on the cap are reported as such.
+//! The caps (with the observed counts) also size a log-factorial table, so their total is bounded
+//! by `MAX_BAG_TOTAL`.
+//!
+//! ## Confidence
+//! Per color: the profile likelihood (others fixed at the estimate), normalized over every count
+//! allowed for that color. i.e. the share of that color's likelihood mass sitting on the estimate.
+//! (1.0 means no other count is plausible; a flat profile spreads it evenly.)
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use tracing::{debug, trace};
+
+use crate::{
+        cubes::{Color, Cubes},
+        custom_error::AocErrorDay02,
+        game::Game,
+};
+
+/// Largest bag total the search will consider (summed over every color's cap).
+pub const MAX_BAG_TOTAL: u64 = 100_000;
+
+/// Knobs for the bag search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InferenceConfig {
+        /// Highest count considered for any color.
+        /// (Raised, per color, to that color's observed minimum if lower.)
+        pub max_per_color: u64,
+        /// Coordinate ascent rounds before giving up on convergence.
+        pub max_rounds:    usize,
+}
+
+impl Default for InferenceConfig {
+        fn default() -> Self {
+                Self { max_per_color: 100,
+                       max_rounds:    50, }
+        }
+}
+
+/// Most likely shared bag, with per-color confidence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BagEstimate {
+        pub bag:            Cubes,
+        pub log_likelihood: f64,
+        /// Share of each color's profile likelihood on the estimated count, in `(0, 1]`.
+        pub confidence:     BTreeMap<Color, f64>,
+        /// Colors whose estimate sits on `max_per_color`; the true count may be higher.
+        pub at_cap:         Vec<Color>,
+        /// Whether coordinate ascent settled within `max_rounds`.
+        pub converged:      bool,
+}
+
+/// Estimate the shared bag with default settings.
+pub fn estimate_bag(games: &[Game]) -> Result<BagEstimate, AocErrorDay02> {
+        estimate_bag_with(games, InferenceConfig::default())
+}
+
+/// Estimate the shared bag.
+/// Errors if the caps (or observed counts) would take the search past `MAX_BAG_TOTAL` cubes.
+pub fn estimate_bag_with(games: &[Game],
+                         config: InferenceConfig)
+                         -> Result<BagEstimate, AocErrorDay02> {
+        let model = Model::new(games, config)?;
+        let mut bag = model.floors.clone();
+        let mut converged = false;
+        for round in 0..config.max_rounds {
+                let mut moved = false;
+                for ci in 0..model.colors.len() {
+                        let (best, _) = model.profile(&bag, ci)
+                                             .fold((bag[ci], f64::NEG_INFINITY), |acc, (v, ll)| {
+                                                     // strict: ties keep the smaller count
+                                                     if ll > acc.1 {
+                                                             (v, ll)
+                                                     }
+                                                     else {
+                                                             acc
+                                                     }
+                                             });
+                        if best != bag[ci] {
+                                trace!(round, color = %model.colors[ci], from = bag[ci], to = best);
+                                bag[ci] = best;
+                                moved = true;
+                        }
+                }
+                if !moved {
+                        debug!(round, "bag estimate converged");
+                        converged = true;
+                        break;
+                }
+        }
+
+        let confidence =
+                (0..model.colors.len()).map(|ci| (model.colors[ci], model.confidence(&bag, ci)))
+                                       .collect();
+        let at_cap = (0..model.colors.len()).filter(|&ci| bag[ci] == model.caps[ci])
+                                            .map(|ci| model.colors[ci])
+                                            .collect();
+        let log_likelihood = model.log_likelihood(&bag);
+        let bag = model.colors
+                       .iter()
+                       .zip(bag)
+                       .fold(Cubes::new(), |mut acc, (&c, v)| {
+                               acc.set(c, v);
+                               acc
+                       });
+        Ok(BagEstimate { bag,
+                         log_likelihood,
+                         confidence,
+                         at_cap,
+                         converged })
+}
+
+/// Draws flattened to count vectors (aligned with `colors`), plus a log-factorial table.
+struct Model {
+        colors:  Vec<Color>,
+        /// `(counts per color, draw size)` for every draw of every game
+        draws:   Vec<(Vec<u64>, u64)>,
+        /// smallest count each color could have
+        floors:  Vec<u64>,
+        /// largest count searched for each color
+        caps:    Vec<u64>,
+        /// `ln(i!)` for `i` up to the largest possible bag total
+        ln_fact: Vec<f64>,
+}
+
+impl Model {
+        fn new(games: &[Game], config: InferenceConfig) -> Result<Self, AocErrorDay02> {
+                let colors: Vec<Color> = games.iter()
+                                              .flat_map(|g| g.draws())
+                                              .flat_map(|d| d.colors())
+                                              .collect::<BTreeSet<_>>()
+                                              .into_iter()
+                                              .collect();
+                let draws: Vec<(Vec<u64>, u64)> = games.iter()
+                                                       .flat_map(|g| g.draws())
+                                                       .map(|d| {
+                                                               let ks: Vec<u64> =
+                                                                       colors.iter()
+                                                                             .map(|&c| d.get(c))
+                                                                             .collect();
+                                                               let n = ks.iter().sum();
+                                                               (ks, n)
+                                                       })
+                                                       .collect();
+                let floors: Vec<u64> = (0..colors.len()).map(|ci| {
+                                                                draws.iter()
+                                                                     .map(|(ks, _)| ks[ci])
+                                                                     .max()
+                                                                     .unwrap_or(0)
+                                                        })
+                                                        .collect();
+                let caps: Vec<u64> = floors.iter()
+                                           .map(|&f| f.max(config.max_per_color))
+                                           .collect();
+                let total = caps.iter()
+                                .fold(0u64, |acc, &c| acc.saturating_add(c));
+                if total > MAX_BAG_TOTAL {
+                        return Err(AocErrorDay02::BagSearchTooLarge { total,
+                                                                      limit: MAX_BAG_TOTAL });
+                }
+                let max_total = total as usize;
+                let mut ln_fact = Vec::with_capacity(max_total + 1);
+                ln_fact.push(0.0);
+                for i in 1..=max_total {
+                        ln_fact.push(ln_fact[i - 1] + (i as f64).ln());
+                }
+                Ok(Self { colors,
+                          draws,
+                          floors,
+                          caps,
+                          ln_fact })
+        }
+
+        fn ln_choose(&self, n: u64, k: u64) -> f64 {
+                debug_assert!(k <= n);
+                let (n, k) = (n as usize, k as usize);
+                self.ln_fact[n] - self.ln_fact[k] - self.ln_fact[n - k]
+        }
+
+        /// Log-likelihood of every draw, given the bag.
+        fn log_likelihood(&self, bag: &[u64]) -> f64 {
+                let total: u64 = bag.iter().sum();
+                self.draws
+                    .iter()
+                    .map(|(ks, n)| {
+                            ks.iter()
+                              .zip(bag)
+                              .map(|(&k, &nc)| self.ln_choose(nc, k))
+                              .sum::<f64>()
+                            - self.ln_choose(total, *n)
+                    })
+                    .sum()
+        }
+
+        /// Log-likelihood for each allowed count of one color, others held fixed.
+        fn profile<'a>(&'a self,
+                       bag: &'a [u64],
+                       ci: usize)
+                       -> impl Iterator<Item = (u64, f64)> + 'a {
+                (self.floors[ci]..=self.caps[ci]).map(move |v| {
+                                                         let mut trial = bag.to_vec();
+                                                         trial[ci] = v;
+                                                         (v, self.log_likelihood(&trial))
+                                                 })
+        }
+
+        /// Normalized profile likelihood at the bag's count for one color.
+        fn confidence(&self, bag: &[u64], ci: usize) -> f64 {
+                let profile: Vec<(u64, f64)> = self.profile(bag, ci)
+                                                   .collect();
+                // shift by the max before exponentiating, so nothing underflows to 0/0
+                let peak = profile.iter()
+                                  .map(|&(_, ll)| ll)
+                                  .fold(f64::NEG_INFINITY, f64::max);
+                let mass: f64 = profile.iter()
+                                       .map(|&(_, ll)| (ll - peak).exp())
+                                       .sum();
+                let at = profile.iter()
+                                .find(|&&(v, _)| v == bag[ci])
+                                .map(|&(_, ll)| (ll - peak).exp())
+                                .unwrap_or(0.0);
+                at / mass
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use indoc::indoc;
+
+        use super::*;
+        use crate::game::parse_games;
+
+        #[test]
+        fn test_single_color_is_flat() -> Result<(), AocErrorDay02> {
+                // with one color every draw is certain, whatever the bag
+                let games = parse_games("Game 1: 3 teal; 5 teal")?;
+                let config = InferenceConfig { max_per_color: 9,
+                                               ..Default::default() };
+                let est = estimate_bag_with(&games, config)?;
+                assert_eq!(est.bag, "5 teal".parse()?);
+                assert!(est.converged);
+                assert!((est.confidence[&Color::intern("teal")] - 1.0 / 5.0).abs() < 1e-9);
+                Ok(())
+        }
+
+        #[test]
+        fn test_pairs_point_to_one_each() -> Result<(), AocErrorDay02> {
+                // a bag of exactly 1 red & 1 blue makes "1 red, 1 blue" certain
+                let games = parse_games("Game 1: 1 red, 1 blue; 1 red, 1 blue")?;
+                let config = InferenceConfig { max_per_color: 4,
+                                               ..Default::default() };
+                let est = estimate_bag_with(&games, config)?;
+                assert_eq!(est.bag, "1 red, 1 blue".parse()?);
+                assert!(est.log_likelihood.abs() < 1e-9);
+                assert!(est.at_cap.is_empty());
+
+                // profile for red, blue = 1: P(draw) = 2 / (r + 1), squared for two draws
+                let mass: f64 = (1..=4).map(|r| (2.0 / (r as f64 + 1.0)).powi(2))
+                                       .sum();
+                assert!((est.confidence[&Color::intern("red")] - 1.0 / mass).abs() < 1e-9);
+                Ok(())
+        }
+
+        #[test]
+        fn test_example_estimate_covers_min_bags() -> Result<(), AocErrorDay02> {
+                let games = parse_games(indoc! {"
+                        Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+                        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+                        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+                        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+                        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+                        "})?;
+                let est = estimate_bag(&games)?;
+                for game in &games {
+                        assert!(game.is_feasible_under(&est.bag));
+                }
+                assert!(est.converged);
+                assert!(est.confidence
+                           .values()
+                           .all(|&c| c > 0.0 && c <= 1.0));
+
+                // the estimate never does worse than where the search started
+                let floor = games.iter()
+                                 .fold(Cubes::new(), |acc, g| acc.max_union(&g.min_bag()));
+                let start = estimate_bag_with(&games, InferenceConfig { max_rounds: 0,
+                                                                        ..Default::default() })?;
+                assert_eq!(start.bag, floor);
+                assert!(est.log_likelihood >= start.log_likelihood);
+                Ok(())
+        }
+
+        #[test]
+        fn test_rejects_huge_searches() -> Result<(), AocErrorDay02> {
+                let games = parse_games("Game 1: 3 teal, 1 red")?;
+                let config = InferenceConfig { max_per_color: u64::MAX,
+                                               ..Default::default() };
+                assert!(matches!(estimate_bag_with(&games, config),
+                                 Err(AocErrorDay02::BagSearchTooLarge { total:
+                                                                                u64::MAX,
+                                                                        .. })));
+
+                // observed counts raise the caps too
+                let games = parse_games("Game 1: 99999999999 teal")?;
+                assert!(matches!(estimate_bag(&games),
+                                 Err(AocErrorDay02::BagSearchTooLarge { .. })));
+                Ok(())
+        }
+}
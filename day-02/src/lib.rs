@@ -3,7 +3,11 @@
 //! And the architecture of the crate will be hierarchical about `part1_lib.rs` and part2_lib.rs`.
 //! (With redundancy common, and by design, between those two files.)
 
+pub mod cubes;
 pub mod custom_error;
+pub mod game;
+pub mod inference;
+pub mod query;
 
 pub mod part1_lib;
 pub mod part2_lib;
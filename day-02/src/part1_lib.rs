@@ -3,112 +3,30 @@
 
 use anyhow::Result;
 
-use crate::custom_error::AocErrorDay02;
-
-/// Example helpfully provided by the `once_cell` crate's documentation.
-///
-/// Simply assigns a string literal pattern to a `Regex` generator
-/// wrapped in a lazy `once_cell` wrapper.
-/// Besides avoiding perf issues with accidental-recompilation
-/// I just find using a `static` for these patterns clear and readable for this code.
-///
-/// # PERF: `regex-automata` crate allows creating a DFA, writing it, and rolling it into binary.
-///  That woudl avoid any initialization cost when running binary, and allow speed optimization
-///  for what are very simple regexes.
-// #[macro_export]
-macro_rules! regex_lazyonce {
-        ($re:expr $(,)?) => {{
-                static RE: once_cell::sync::OnceCell<regex::Regex> =
-                        once_cell::sync::OnceCell::new();
-                RE.get_or_init(|| regex::Regex::new($re).unwrap())
-        }};
-}
+use crate::{
+        cubes::{default_bag, Cubes},
+        custom_error::AocErrorDay02,
+        game::parse_games,
+};
 
 // // for testing for pathological inputs
-// const DUMB_PAT: &str = r"(\d+) (\w+), (\d+) (\w+), (\d+) (\w+), (\d+) (\w+);";
-//                 regex_lazyonce!(DUMB_PAT).captures_iter(line).for_each(|c| {
+// patterns! { DUMB_PAT = r"(\d+) (\w+), (\d+) (\w+), (\d+) (\w+), (\d+) (\w+);"; }
+//                 DUMB_PAT.captures_iter(line).for_each(|c| {
 //                         let (needle, _): (&str, [&str; 6]) = c.extract();
 //                         info!("{:?}", needle);
 //                 });
-const ID_PAT: &str = r"Game (\d+):";
-const RED_PAT: &str = r"(\d+) red";
-const GREEN_PAT: &str = r"(\d+) green";
-const BLUE_PAT: &str = r"(\d+) blue";
-const MAX_CUBES: Cubes = Cubes { red:   12,
-                                 green: 13,
-                                 blue:  14, };
-
-/// set of cubes
-#[derive(Debug, PartialEq)]
-struct Cubes {
-        red:   u64,
-        green: u64,
-        blue:  u64,
-}
-
-impl Cubes {
-        /// whether a is a ~subset (inclusive) of b
-        fn is_subset_of(&self, other: &Self) -> bool {
-                (self.red <= other.red) && (self.green <= other.green) && (self.blue <= other.blue)
-        }
-}
-
 // #[tracing::instrument]
 pub fn process(input: &str) -> Result<u64, AocErrorDay02> {
-        let mut id_sum = 0;
-        for line in input.lines() {
-                let (id, line_cubes) = extract_data(line);
-                if line_cubes.is_subset_of(&MAX_CUBES) {
-                        id_sum += id;
-                }
-        }
-        Ok(id_sum)
+        process_with_bag(input, &default_bag())
 }
 
-/// REFACTOR:
-/// yuuuch at repeated code.
-/// Not sure of  a better way to do this if usign regex_macro
-/// ... I should just have defined the regex as static with lazy
-/// and then iterated over them.
-fn extract_data(hay: &str) -> (u64, Cubes) {
-        let id = regex_lazyonce!(ID_PAT).captures(hay)
-                                        .expect("captures iter failure")
-                                        .get(1)
-                                        .map(|v| {
-                                                v.as_str()
-                                                 .parse::<u64>()
-                                                 .expect("id parse failure")
-                                        })
-                                        .expect("iteration failure");
-        let r_sum = regex_lazyonce!(RED_PAT).captures_iter(hay)
-                                            .map(|c| {
-                                                    let (_, [val]) = c.extract();
-                                                    val.parse::<u64>()
-                                                       .expect("red parse failure")
-                                            })
-                                            .max()
-                                            .unwrap_or(0);
-        let g_sum = regex_lazyonce!(GREEN_PAT).captures_iter(hay)
-                                              .map(|c| {
-                                                      let (_, [val]) = c.extract();
-                                                      val.parse::<u64>()
-                                                         .expect("green parse failure")
-                                              })
-                                              .max()
-                                              .unwrap_or(0);
-        let b_sum = regex_lazyonce!(BLUE_PAT).captures_iter(hay)
-                                             .map(|c| {
-                                                     let (_, [val]) = c.extract();
-                                                     val.parse::<u64>()
-                                                        .expect("blue parse failure")
-                                             })
-                                             .max()
-                                             .unwrap_or(0);
-
-        (id,
-         Cubes { red:   r_sum,
-                 green: g_sum,
-                 blue:  b_sum, })
+/// Sum of ids of games possible with the given bag.
+/// (Any color is picked up, not just red, green, and blue. Blank lines are skipped.)
+pub fn process_with_bag(input: &str, bag: &Cubes) -> Result<u64, AocErrorDay02> {
+        Ok(parse_games(input)?.iter()
+                              .filter(|game| game.is_feasible_under(bag))
+                              .map(|game| game.id())
+                              .sum())
 }
 
 #[cfg(test)]
@@ -119,7 +37,7 @@ mod tests {
 
         #[test]
         fn test_process_example() -> Result<()> {
-                tracing_subscriber::fmt::init();
+                let _ = tracing_subscriber::fmt::try_init();
 
                 let input = indoc! {"
                         Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
@@ -130,6 +48,22 @@ mod tests {
                         "};
                 let expected = 8;
                 assert_eq!(process(input)?, expected);
+                // blank lines aren't games
+                assert_eq!(process(&format!("\n{}\n\n", input.replace('\n', "\n\n")))?,
+                           expected);
+                Ok(())
+        }
+
+        #[test]
+        fn test_process_with_unknown_color() -> Result<()> {
+                let input = indoc! {"
+                        Game 1: 3 blue, 4 red; 2 yellow
+                        Game 2: 1 blue, 2 green
+                        "};
+                assert_eq!(process(input)?, 2);
+
+                let bag: Cubes = "12 red, 13 green, 14 blue, 2 yellow".parse()?;
+                assert_eq!(process_with_bag(input, &bag)?, 3);
                 Ok(())
         }
 
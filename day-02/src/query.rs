@@ -0,0 +1,222 @@
+//! Constraint queries over parsed Day 02 games.
+//!
+//! Part 1 only asks whether each game fits the 12/13/14 bag.
+//! These let us ask the neighboring questions without writing a new `process` each time:
+//! - minimum bag per game
+//! - games feasible under a given bag
+//! - smallest bag under which at least N games are feasible
+//! - per-color histograms of draw counts
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use itertools::Itertools;
+use tracing::debug;
+
+use crate::{
+        cubes::{Color, Cubes},
+        custom_error::AocErrorDay02,
+        game::{parse_games, Game},
+};
+
+/// Collection of games to run queries against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameQuery {
+        games: Vec<Game>,
+}
+
+impl GameQuery {
+        pub fn new(games: Vec<Game>) -> Self {
+                Self { games }
+        }
+
+        /// Parse puzzle input directly into a queryable collection.
+        pub fn parse(input: &str) -> Result<Self, AocErrorDay02> {
+                Ok(Self::new(parse_games(input)?))
+        }
+
+        pub fn games(&self) -> &[Game] {
+                &self.games
+        }
+
+        /// Every color mentioned in any draw of any game.
+        pub fn colors(&self) -> BTreeSet<Color> {
+                self.games
+                    .iter()
+                    .flat_map(|g| g.draws())
+                    .flat_map(|d| d.colors())
+                    .collect()
+        }
+
+        /// Minimum bag for each game, by game id.
+        pub fn min_bags(&self) -> Vec<(u64, Cubes)> {
+                self.games
+                    .iter()
+                    .map(|g| (g.id(), g.min_bag()))
+                    .collect()
+        }
+
+        /// Ids of the games possible with the given bag.
+        pub fn feasible_under(&self, bag: &Cubes) -> Vec<u64> {
+                self.games
+                    .iter()
+                    .filter(|g| g.is_feasible_under(bag))
+                    .map(|g| g.id())
+                    .collect()
+        }
+
+        /// Smallest bag (by total cube count) under which at least `n` games are feasible.
+        /// Returns `None` if there are fewer than `n` games.
+        ///
+        /// ## Approach
+        /// The best bag's count for each color is always some game's minimum for that color
+        /// (anything in between could be lowered without losing a game), so we only search
+        /// over those candidate thresholds.
+        /// Ties on total are broken by power, then by first found.
+        /// (Colors are searched in interned order, so between exact ties the pick is arbitrary.)
+        ///
+        /// PERF: the search is the product of distinct thresholds per color.
+        /// Fine for a handful of colors with small counts; exponential in colors in general.
+        pub fn smallest_bag_for(&self, n: usize) -> Option<Cubes> {
+                if n > self.games.len() {
+                        return None;
+                }
+                let colors: Vec<Color> = self.colors()
+                                             .into_iter()
+                                             .collect();
+                if colors.is_empty() {
+                        return Some(Cubes::new());
+                }
+                // each game's minimum bag as a vector, aligned with `colors`
+                let mins: Vec<Vec<u64>> = self.games
+                                              .iter()
+                                              .map(|g| {
+                                                      let bag = g.min_bag();
+                                                      colors.iter()
+                                                            .map(|&c| bag.get(c))
+                                                            .collect()
+                                              })
+                                              .collect();
+                let thresholds = (0..colors.len()).map(|ci| candidate_thresholds(&mins, ci));
+
+                let (total, _, limits) =
+                        thresholds.multi_cartesian_product()
+                                  .filter(|limits| {
+                                          mins.iter()
+                                              .filter(|m| fits_under(m, limits))
+                                              .count()
+                                          >= n
+                                  })
+                                  .map(|limits| {
+                                          (limits.iter().sum::<u64>(),
+                                           limits.iter().product::<u64>(),
+                                           limits)
+                                  })
+                                  .min_by_key(|(total, power, _)| (*total, *power))?;
+                debug!(?total, "smallest bag found");
+
+                // colors with a zero limit are left out, rather than listed as `0 <color>`
+                Some(colors.into_iter()
+                           .zip(limits)
+                           .filter(|&(_, v)| v > 0)
+                           .fold(Cubes::new(), |mut acc, (c, v)| {
+                                   acc.set(c, v);
+                                   acc
+                           }))
+        }
+
+        /// For each color: how many draws showed each count of that color.
+        /// Only draws that mention a color contribute to its histogram.
+        pub fn color_histograms(&self) -> BTreeMap<Color, BTreeMap<u64, usize>> {
+                let mut hists: BTreeMap<Color, BTreeMap<u64, usize>> = BTreeMap::new();
+                for (color, count) in self.games
+                                          .iter()
+                                          .flat_map(|g| g.draws())
+                                          .flat_map(|d| d.iter())
+                {
+                        *hists.entry(color)
+                              .or_default()
+                              .entry(count)
+                              .or_insert(0) += 1;
+                }
+                hists
+        }
+}
+
+/// Sorted distinct values (plus zero) seen for one color across the games' minimum bags.
+fn candidate_thresholds(mins: &[Vec<u64>], color_idx: usize) -> Vec<u64> {
+        let set: BTreeSet<u64> = mins.iter()
+                                     .map(|m| m[color_idx])
+                                     .chain([0])
+                                     .collect();
+        set.into_iter().collect()
+}
+
+/// Whether a minimum bag fits under the given limits, color by color.
+fn fits_under(min_bag: &[u64], limits: &[u64]) -> bool {
+        min_bag.iter()
+               .zip(limits)
+               .all(|(need, have)| need <= have)
+}
+
+#[cfg(test)]
+mod tests {
+        use indoc::indoc;
+
+        use super::*;
+        use crate::cubes::default_bag;
+
+        const EXAMPLE: &str = indoc! {"
+                Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+                Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+                Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+                Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+                Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+                "};
+
+        #[test]
+        fn test_min_bags_and_feasible() -> Result<(), AocErrorDay02> {
+                let query = GameQuery::parse(EXAMPLE)?;
+                let powers: Vec<u64> = query.min_bags()
+                                            .iter()
+                                            .map(|(_, bag)| bag.power(query.colors()))
+                                            .collect();
+                assert_eq!(powers, vec![48, 12, 1560, 630, 36]);
+                assert_eq!(query.feasible_under(&default_bag()), vec![1, 2, 5]);
+                Ok(())
+        }
+
+        #[test]
+        fn test_smallest_bag_for() -> Result<(), AocErrorDay02> {
+                let query = GameQuery::parse(EXAMPLE)?;
+                let all = query.smallest_bag_for(5)
+                               .expect("5 games available");
+                assert_eq!(all, "20 red, 13 green, 15 blue".parse()?);
+                assert_eq!(query.feasible_under(&all)
+                                .len(),
+                           5);
+
+                // games 1 & 2 or 2 & 5 tie as the cheapest pairs, both at 13 cubes
+                let two = query.smallest_bag_for(2)
+                               .expect("5 games available");
+                assert_eq!(two.iter()
+                              .map(|(_, n)| n)
+                              .sum::<u64>(),
+                           13);
+                assert_eq!(query.feasible_under(&two)
+                                .len(),
+                           2);
+                assert_eq!(query.smallest_bag_for(0), Some(Cubes::new()));
+                assert_eq!(query.smallest_bag_for(6), None);
+                Ok(())
+        }
+
+        #[test]
+        fn test_color_histograms() -> Result<(), AocErrorDay02> {
+                let query = GameQuery::parse(EXAMPLE)?;
+                let hists = query.color_histograms();
+                let green = &hists[&Color::intern("green")];
+                assert_eq!(green.get(&2), Some(&4));
+                assert_eq!(green.values().sum::<usize>(), 13);
+                Ok(())
+        }
+}
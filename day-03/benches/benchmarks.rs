@@ -1,8 +1,15 @@
 //! Benchmarking code for use with **divan** crate.
 //! Specifies two functions, corresponding to two parts of the
 //! Day 03 of Advent of Code 2023 problem.
+//! (Plus streaming vs. register versions on generated schematics, by side length.)
 
-use day_03::*;
+use day_03::{
+        generate::{generate, SchematicConfig},
+        *,
+};
+use divan::Bencher;
+
+const SIDES: &[usize] = &[140, 1_000, 3_000];
 
 fn main() {
         // Run registered benchmarks.
@@ -18,3 +25,47 @@ fn part1() {
 fn part2() {
         part2_lib::process(divan::black_box(include_str!("../input2.txt",))).unwrap();
 }
+
+#[divan::bench]
+fn part1_registers() {
+        part1_lib::process_registers(divan::black_box(include_str!("../input1.txt",))).unwrap();
+}
+
+#[divan::bench]
+fn part2_registers() {
+        part2_lib::process_registers(divan::black_box(include_str!("../input2.txt",))).unwrap();
+}
+
+#[divan::bench]
+fn part1_streaming() {
+        part1_lib::process_streaming(divan::black_box(include_str!("../input1.txt",).as_bytes())).unwrap();
+}
+
+#[divan::bench]
+fn part2_streaming() {
+        part2_lib::process_streaming(divan::black_box(include_str!("../input2.txt",).as_bytes())).unwrap();
+}
+
+#[divan::bench(args = SIDES)]
+fn generated_part1_registers(bencher: Bencher, side: usize) {
+        let input = generate(&SchematicConfig::square(side));
+        bencher.bench(|| part1_lib::process_registers(divan::black_box(&input)).unwrap());
+}
+
+#[divan::bench(args = SIDES)]
+fn generated_part1_streaming(bencher: Bencher, side: usize) {
+        let input = generate(&SchematicConfig::square(side));
+        bencher.bench(|| part1_lib::process_streaming(divan::black_box(input.as_bytes())).unwrap());
+}
+
+#[divan::bench(args = SIDES)]
+fn generated_part2_registers(bencher: Bencher, side: usize) {
+        let input = generate(&SchematicConfig::square(side));
+        bencher.bench(|| part2_lib::process_registers(divan::black_box(&input)).unwrap());
+}
+
+#[divan::bench(args = SIDES)]
+fn generated_part2_streaming(bencher: Bencher, side: usize) {
+        let input = generate(&SchematicConfig::square(side));
+        bencher.bench(|| part2_lib::process_streaming(divan::black_box(input.as_bytes())).unwrap());
+}
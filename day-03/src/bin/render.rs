@@ -0,0 +1,65 @@
+//! Render CLI for Day 03 of Advent of Code 2023.
+//! Prints `input1.txt` (or `--input <path>`) with parts, non-parts, gears and halos colored.
+//!
+//! ## Usage
+//! ```text
+//! render [--input <path>]                  ANSI to the terminal, with a legend
+//! render [--input <path>] --html <out>     standalone HTML page
+//! ```
+
+use aoc_common::grid::Grid;
+use day_03::render::Annotated;
+use miette::{miette, Context, IntoDiagnostic, Result};
+
+const USAGE: &str = "usage: render [--input <path>] [--html <out>]";
+
+/// Value following `flag`, removed from `args` along with the flag.
+fn take_flag(args: &mut Vec<String>, flag: &str) -> Result<Option<String>> {
+        let Some(i) = args.iter()
+                          .position(|a| a == flag)
+        else {
+                return Ok(None);
+        };
+        let value = args.get(i + 1)
+                        .ok_or_else(|| miette!("{} needs a path\n{}", flag, USAGE))?
+                        .clone();
+        args.drain(i..=i + 1);
+        Ok(Some(value))
+}
+
+fn main() -> Result<()> {
+        tracing_subscriber::fmt::init();
+
+        let mut args: Vec<String> = std::env::args().skip(1)
+                                                    .collect();
+        let input = match take_flag(&mut args, "--input")? {
+                Some(path) =>
+                        std::fs::read_to_string(&path).into_diagnostic()
+                                                      .wrap_err_with(|| format!("read {}", path))?,
+                None => include_str!("../../input1.txt").to_string(),
+        };
+        let html_out = take_flag(&mut args, "--html")?;
+        if !args.is_empty() {
+                return Err(miette!("{}", USAGE));
+        }
+
+        let grid: Grid<u8> = input.parse()
+                                  .into_diagnostic()
+                                  .context("parse schematic")?;
+        let annotated = Annotated::new(&grid)?;
+        match html_out {
+                Some(path) => {
+                        std::fs::write(&path, annotated.to_html()).into_diagnostic()
+                                                                  .wrap_err_with(|| {
+                                                                          format!("write {}", path)
+                                                                  })?;
+                        println!("wrote {}", path);
+                },
+                None => {
+                        print!("{}", annotated.to_ansi());
+                        println!();
+                        print!("{}", annotated.ansi_legend());
+                },
+        }
+        Ok(())
+}
@@ -0,0 +1,295 @@
+//! Connected-component view of a Day 03 schematic.
+//!
+//! One labeling pass over the grid gives every number and every symbol a label,
+//! its cells, and its bounding box.
+//! Labels that touch (diagonals included) are joined in an adjacency graph,
+//! so "part numbers" and "gears" are just questions about that graph.
+//!
+//! ## Connectivity
+//! - digits join the digit to their left (numbers never span rows)
+//! - each symbol cell is its own component
+//! - `.` is background
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use aoc_common::grid::{Grid, Pos};
+use tracing::debug;
+
+use crate::custom_error::AocErrorDay03;
+
+/// Id of a component; index into `Schematic::components`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Label(pub usize);
+
+/// What a component is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+        Number(u64),
+        Symbol(u8),
+}
+
+/// Smallest rectangle holding a component, corners inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+        pub top_left:     Pos,
+        pub bottom_right: Pos,
+}
+
+impl BoundingBox {
+        fn of(pos: Pos) -> Self {
+                Self { top_left:     pos,
+                       bottom_right: pos, }
+        }
+
+        fn grow(&mut self, pos: Pos) {
+                self.top_left = Pos::new(self.top_left.row.min(pos.row),
+                                         self.top_left.col.min(pos.col));
+                self.bottom_right = Pos::new(self.bottom_right
+                                                 .row
+                                                 .max(pos.row),
+                                             self.bottom_right
+                                                 .col
+                                                 .max(pos.col));
+        }
+}
+
+/// A labeled region of the schematic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+        pub label: Label,
+        pub kind:  Kind,
+        pub cells: Vec<Pos>,
+        pub bbox:  BoundingBox,
+}
+
+impl Component {
+        pub fn number(&self) -> Option<u64> {
+                match self.kind {
+                        Kind::Number(n) => Some(n),
+                        Kind::Symbol(_) => None,
+                }
+        }
+
+        pub fn is_symbol(&self) -> bool {
+                matches!(self.kind, Kind::Symbol(_))
+        }
+}
+
+/// A labeled schematic and its adjacency graph.
+#[derive(Debug, Clone)]
+pub struct Schematic {
+        labels:     Grid<Option<Label>>,
+        components: Vec<Component>,
+        adjacency:  BTreeMap<Label, BTreeSet<Label>>,
+}
+
+impl Schematic {
+        pub fn parse(input: &str) -> Result<Self, AocErrorDay03> {
+                Self::from_grid(&input.parse()?)
+        }
+
+        /// Label every component, then link every pair of labels that touch.
+        pub fn from_grid(grid: &Grid<u8>) -> Result<Self, AocErrorDay03> {
+                let mut labels: Grid<Option<Label>> = Grid::new(grid.height(), grid.width(), None);
+                let mut components: Vec<Component> = Vec::new();
+
+                for (pos, &b) in grid.iter() {
+                        if b == b'.' {
+                                continue;
+                        }
+                        let left = grid.step(pos, 0, -1)
+                                       .and_then(|p| labels[p]);
+                        let joins_left = b.is_ascii_digit()
+                                         && left.is_some_and(|l| {
+                                                        matches!(components[l.0].kind,
+                                                                 Kind::Number(_))
+                                                });
+                        let label = match left {
+                                Some(l) if joins_left => {
+                                        let comp = &mut components[l.0];
+                                        comp.cells.push(pos);
+                                        comp.bbox.grow(pos);
+                                        l
+                                },
+                                _ => {
+                                        let label = Label(components.len());
+                                        let kind = if b.is_ascii_digit() {
+                                                Kind::Number(0)
+                                        }
+                                        else {
+                                                Kind::Symbol(b)
+                                        };
+                                        components.push(Component { label,
+                                                                    kind,
+                                                                    cells: vec![pos],
+                                                                    bbox: BoundingBox::of(pos) });
+                                        label
+                                },
+                        };
+                        labels[pos] = Some(label);
+                }
+
+                // digits are all placed; now read each number's value off its cells
+                for comp in &mut components {
+                        if let Kind::Number(n) = &mut comp.kind {
+                                let digits: Vec<u8> = comp.cells
+                                                          .iter()
+                                                          .map(|&p| grid[p])
+                                                          .collect();
+                                *n = read_number(&digits, comp.cells[0])?;
+                        }
+                }
+
+                let mut adjacency: BTreeMap<Label, BTreeSet<Label>> = BTreeMap::new();
+                for comp in &components {
+                        let touching: BTreeSet<Label> = comp.cells
+                                                            .iter()
+                                                            .flat_map(|&p| labels.neighbors8(p))
+                                                            .filter_map(|p| labels[p])
+                                                            .filter(|&l| l != comp.label)
+                                                            .collect();
+                        adjacency.insert(comp.label, touching);
+                }
+                debug!(components = components.len(), "schematic labeled");
+
+                Ok(Self { labels,
+                          components,
+                          adjacency })
+        }
+
+        pub fn components(&self) -> &[Component] {
+                &self.components
+        }
+
+        pub fn component(&self, label: Label) -> &Component {
+                &self.components[label.0]
+        }
+
+        /// Label covering a cell, if any.
+        pub fn label_at(&self, pos: Pos) -> Option<Label> {
+                self.labels
+                    .get(pos)
+                    .copied()
+                    .flatten()
+        }
+
+        /// Components touching the given one.
+        pub fn neighbors(&self, label: Label) -> impl Iterator<Item = &Component> {
+                self.adjacency[&label].iter()
+                                      .map(|&l| self.component(l))
+        }
+
+        /// Numbers touching at least one symbol.
+        pub fn part_numbers(&self) -> impl Iterator<Item = &Component> {
+                self.components
+                    .iter()
+                    .filter(|c| c.number().is_some())
+                    .filter(|c| {
+                            self.neighbors(c.label)
+                                .any(Component::is_symbol)
+                    })
+        }
+
+        /// `*` symbols touching exactly two numbers, with those two numbers.
+        pub fn gears(&self) -> impl Iterator<Item = (&Component, [u64; 2])> {
+                self.components
+                    .iter()
+                    .filter(|c| c.kind == Kind::Symbol(b'*'))
+                    .filter_map(|c| {
+                            let nums: Vec<u64> = self.neighbors(c.label)
+                                                     .filter_map(Component::number)
+                                                     .collect();
+                            <[u64; 2]>::try_from(nums).ok()
+                                                      .map(|pair| (c, pair))
+                    })
+        }
+}
+
+/// Value of a number's digits, the first at `at`; `NumberTooBig` past `u64`.
+/// Every reading of the schematic (components, streaming, registers) goes through this.
+pub fn read_number(digits: &[u8], at: Pos) -> Result<u64, AocErrorDay03> {
+        digits.iter()
+              .try_fold(0u64, |acc, &d| {
+                      acc.checked_mul(10)?
+                         .checked_add(u64::from(d - b'0'))
+              })
+              .ok_or_else(|| {
+                      let digits = String::from_utf8_lossy(digits).into_owned();
+                      AocErrorDay03::NumberTooBig { digits, at }
+              })
+}
+
+#[cfg(test)]
+mod tests {
+        use indoc::indoc;
+
+        use super::*;
+
+        const EXAMPLE: &str = indoc! {"
+                467..114..
+                ...*......
+                ..35..633.
+                ......#...
+                617*......
+                .....+.58.
+                ..592.....
+                ......755.
+                ...$.*....
+                .664.598..
+        "};
+
+        #[test]
+        fn test_labeling() -> Result<(), AocErrorDay03> {
+                let schematic = Schematic::parse(EXAMPLE)?;
+                let numbers = schematic.components()
+                                       .iter()
+                                       .filter(|c| c.number().is_some())
+                                       .count();
+                assert_eq!(numbers, 10);
+                assert_eq!(schematic.components().len(), 16);
+
+                let first = schematic.component(schematic.label_at(Pos::new(0, 1))
+                                                         .expect("inside 467"));
+                assert_eq!(first.kind, Kind::Number(467));
+                assert_eq!(first.cells.len(), 3);
+                assert_eq!(first.bbox, BoundingBox { top_left:     Pos::new(0, 0),
+                                                     bottom_right: Pos::new(0, 2), });
+                assert_eq!(schematic.label_at(Pos::new(0, 3)), None);
+                Ok(())
+        }
+
+        #[test]
+        fn test_graph_queries() -> Result<(), AocErrorDay03> {
+                let schematic = Schematic::parse(EXAMPLE)?;
+                let not_parts: Vec<u64> = schematic.components()
+                                                   .iter()
+                                                   .filter_map(Component::number)
+                                                   .filter(|n| {
+                                                           !schematic.part_numbers()
+                                                   .any(|c| c.number() == Some(*n))
+                                                   })
+                                                   .collect();
+                assert_eq!(not_parts, vec![114, 58]);
+
+                let gears: Vec<[u64; 2]> = schematic.gears()
+                                                    .map(|(_, pair)| pair)
+                                                    .collect();
+                assert_eq!(gears, vec![[467, 35], [755, 598]]);
+                Ok(())
+        }
+
+        #[test]
+        fn test_adjacent_numbers_stay_separate() -> Result<(), AocErrorDay03> {
+                // 12 and 34 touch diagonally, and 5 sits right under 34: three numbers, one symbol
+                let schematic = Schematic::parse("12..\n..34\n...5\n#...\n")?;
+                let numbers: Vec<u64> = schematic.components()
+                                                 .iter()
+                                                 .filter_map(Component::number)
+                                                 .collect();
+                assert_eq!(numbers, vec![12, 34, 5]);
+                assert_eq!(schematic.part_numbers()
+                                    .count(),
+                           0);
+                Ok(())
+        }
+}
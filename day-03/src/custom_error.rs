@@ -1,5 +1,6 @@
 //! Custom Error type for Day 03 of Advent of Code 2023.
 
+use aoc_common::grid::{GridError, Pos};
 use miette::Diagnostic;
 use thiserror::Error;
 
@@ -8,4 +9,16 @@ pub enum AocErrorDay03 {
         #[error(transparent)]
         #[diagnostic(code(aoc::io_error))]
         IoError(#[from] std::io::Error),
+
+        #[error(transparent)]
+        #[diagnostic(transparent)]
+        Grid(#[from] GridError),
+
+        #[error("Number {digits} at {at} is too big for a u64")]
+        #[diagnostic(code(aoc::number_too_big))]
+        NumberTooBig { digits: String, at: Pos },
+
+        #[error("Error parsing rule: {0}")]
+        #[diagnostic(code(aoc::rule_parse_error))]
+        RuleParse(String),
 }
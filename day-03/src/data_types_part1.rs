@@ -1,14 +1,14 @@
 //! Objects for Day-03
 use std::collections::HashSet;
 
+use aoc_common::grid::{Grid, Pos, Run};
 use derive_more::{AsMut, AsRef, Constructor, IntoIterator};
-use itertools::Itertools;
-use once_cell::sync::Lazy;
-use regex::Regex;
 use tracing::info;
 
+use crate::{components::read_number, custom_error::AocErrorDay03};
+
 /// A simple vec of relevant info for each number
-#[derive(AsRef, AsMut, IntoIterator, Debug)]
+#[derive(AsRef, AsMut, IntoIterator, Debug, Default)]
 pub struct NumberRegister {
         vec: Vec<NumberInfo>,
 }
@@ -16,35 +16,26 @@ pub struct NumberRegister {
 impl NumberRegister {
         /// Constructor
         pub fn new() -> Self {
-                Self { vec: Vec::new() }
+                Self::default()
         }
 
-        /// Register a number and its info
-        pub fn register_numbers(&mut self, row: i64, raw_line: &str) {
-                static RE_NUMBER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
-
-                RE_NUMBER.find_iter(raw_line)
-                         .for_each(|m| {
-                                 let (h_start, _) = (m.start() as i64, m.end());
-                                 let len = m.len() as i64;
-                                 let val = m.as_str()
-                                            .parse::<u64>()
-                                            .expect("parse failure");
-                                 let start_loc = (row, h_start);
-                                 let numinfo = NumberInfo::new(start_loc, len, val);
-                                 self.vec.push(numinfo);
-                         });
+        /// Register every number in the grid (each a horizontal run of digits)
+        pub fn register_numbers(&mut self, grid: &Grid<u8>) -> Result<(), AocErrorDay03> {
+                for run in grid.runs(u8::is_ascii_digit) {
+                        let val = read_number(grid.run_cells(run), run.start())?;
+                        self.vec
+                            .push(NumberInfo::new(run, val));
+                }
+                Ok(())
         }
 }
 
 /// Info needed to work with each number
-/// start_Location as a unique id and seed for location values
-/// len to calculate all values touched by number
+/// The run's start doubles as a unique id; the run gives all locations touched by the number
 #[derive(Constructor, AsRef, AsMut, Debug)]
 pub struct NumberInfo {
-        start_loc: (i64, i64),
-        len:       i64,
-        val:       u64,
+        run: Run,
+        val: u64,
 }
 
 impl PartialEq for NumberInfo {
@@ -56,20 +47,13 @@ impl PartialEq for NumberInfo {
 impl Eq for NumberInfo {}
 
 impl NumberInfo {
-        pub fn id(&self) -> (i64, i64) {
-                self.start_loc
+        pub fn id(&self) -> Pos {
+                self.run.start()
         }
 
         /// Locations that a number covers (i.e. position of each digit)
-        pub fn locations(&self) -> Vec<(i64, i64)> {
-                let (row, col) = self.start_loc;
-                let mut out = Vec::new();
-                for rδ in 0..self.len {
-                        for cδ in 0..self.len {
-                                out.push((row, col + cδ));
-                        }
-                }
-                out
+        pub fn locations(&self) -> Vec<Pos> {
+                self.run.positions().collect()
         }
 
         pub fn val(&self) -> u64 {
@@ -78,56 +62,52 @@ impl NumberInfo {
 }
 
 /// All points touched by Special Chars
-#[derive(AsRef, AsMut, IntoIterator, Debug)]
+#[derive(AsRef, AsMut, IntoIterator, Debug, Default)]
 pub struct SpecialAdjacenciesRegister {
-        set: HashSet<(i64, i64)>,
+        set: HashSet<Pos>,
 }
 
 impl SpecialAdjacenciesRegister {
         /// Constructor
         pub fn new() -> Self {
-                Self { set: HashSet::new(), }
+                Self::default()
         }
 
-        /// Register all adjacencies (inclusive of number itself)
-        pub fn register_special_adjacencies(&mut self, row: i64, raw_line: &str) {
-                // `[^.\d]` any char that's neither a literal `.` nor digit
-                // (`.` is taken literally inside brackets, vs being an almost-any char normally)
-                static RE_SPECIAL: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^.\d]").unwrap());
-                RE_SPECIAL.find_iter(raw_line)
-                          .for_each(|m| {
-                                  info!("m: {:?}", m);
-                                  let char_loc = m.start() as i64;
-                                  self.calculate_adjacencies(row, char_loc);
-                          });
-        }
-
-        /// all the locations of a char +/-1
-        /// WARNING: no bounds are checked
-        /// For our current problems this is merely a minor perf issue at most (possibly a perf itself)
-        /// As number digits cannot exist outside bounds
-        /// NOTE: we are not allowing the char itself to be represented
-        /// This is appropriate to our problem, but arguably unintuivie
-        /// WARNING: we assume row lengths to be more than one less than i64::MAX
-        /// TODO: consider ways of propogating constraints -- so that guards aren't being re-checked
-        /// constantly locally..
-        fn calculate_adjacencies(&mut self, row: i64, col: i64) {
-                // to avoid recasting we use delta of + of our fix
-                let it_δ = (0..=2).cartesian_product(0..=2)
-                                  .map(|(x, y)| (x - 1, y - 1));
-                let it_adj = it_δ.map(|(rδ, cδ)| (rδ + row, cδ + col));
-                // // TODO: check bounds
-                // // for right now this is merely a PERF issue
-                // if r < 0 || c < 0  || r > r_max || c > c_max {
-
-                for p in it_adj {
-                        self.set.insert(p);
-                }
+        /// Register all adjacencies of every special char
+        /// (any char that's neither a literal `.` nor digit)
+        pub fn register_special_adjacencies(&mut self, grid: &Grid<u8>) {
+                grid.iter()
+                    .filter(|(_, &b)| b != b'.' && !b.is_ascii_digit())
+                    .for_each(|(pos, _)| {
+                            info!("special at: {}", pos);
+                            self.set
+                                .extend(grid.neighbors8(pos));
+                    });
         }
 
         /// Checks if location as point set is in register
         /// (just skips wrapper to check hashset)
-        pub fn contains(&self, loc: (i64, i64)) -> bool {
+        pub fn contains(&self, loc: Pos) -> bool {
                 self.set.contains(&loc)
         }
 }
+
+#[cfg(test)]
+mod tests {
+        use super::*;
+
+        #[test]
+        fn test_locations_one_per_digit() {
+                let grid: Grid<u8> = "..467.\n".parse().unwrap();
+                let mut numbers = NumberRegister::new();
+                numbers.register_numbers(&grid)
+                       .unwrap();
+                let number = numbers.into_iter()
+                                    .next()
+                                    .expect("one number");
+                assert_eq!(number.val(), 467);
+                assert_eq!(number.locations(), vec![Pos::new(0, 2),
+                                                    Pos::new(0, 3),
+                                                    Pos::new(0, 4)]);
+        }
+}
@@ -0,0 +1,96 @@
+//! Random schematics, for benchmarking at sizes well past the puzzle input.
+//!
+//! Rows are filled left to right: a number (1-3 digits), a symbol, or a `.`,
+//! with a `.` after every number so numbers stay separate within a row.
+//! Seeded, so a given config always gives the same schematic.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Shape and density of a generated schematic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SchematicConfig {
+        pub height:         usize,
+        pub width:          usize,
+        /// chance a cell starts a number
+        pub number_density: f64,
+        /// chance a cell is a symbol
+        pub symbol_density: f64,
+        /// share of symbols that are `*`
+        pub gear_share:     f64,
+        pub seed:           u64,
+}
+
+impl SchematicConfig {
+        /// Square schematic with roughly the puzzle input's densities.
+        pub fn square(side: usize) -> Self {
+                Self { height:         side,
+                       width:          side,
+                       number_density: 0.12,
+                       symbol_density: 0.04,
+                       gear_share:     0.4,
+                       seed:           2023, }
+        }
+}
+
+const SYMBOLS: &[u8] = b"#$%&+-/=@";
+
+/// A schematic as text, one `\n`-terminated line per row.
+pub fn generate(config: &SchematicConfig) -> String {
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let mut out = String::with_capacity(config.height * (config.width + 1));
+        let mut row = Vec::with_capacity(config.width);
+        for _ in 0..config.height {
+                row.clear();
+                while row.len() < config.width {
+                        let room = config.width - row.len();
+                        let roll: f64 = rng.gen();
+                        if roll < config.number_density {
+                                let digits = rng.gen_range(1..=3).min(room);
+                                row.push(rng.gen_range(b'1'..=b'9'));
+                                for _ in 1..digits {
+                                        row.push(rng.gen_range(b'0'..=b'9'));
+                                }
+                                if row.len() < config.width {
+                                        row.push(b'.');
+                                }
+                        }
+                        else if roll < config.number_density + config.symbol_density {
+                                let gear = rng.gen_bool(config.gear_share);
+                                row.push(if gear {
+                                           b'*'
+                                   }
+                                   else {
+                                           SYMBOLS[rng.gen_range(0..SYMBOLS.len())]
+                                   });
+                        }
+                        else {
+                                row.push(b'.');
+                        }
+                }
+                out.extend(row.iter().map(|&b| b as char));
+                out.push('\n');
+        }
+        out
+}
+
+#[cfg(test)]
+mod tests {
+        use super::*;
+        use crate::{part1_lib, part2_lib, streaming};
+
+        #[test]
+        fn test_generated_agrees_everywhere() -> Result<(), crate::custom_error::AocErrorDay03> {
+                let input = generate(&SchematicConfig::square(60));
+                assert_eq!(input.lines().count(), 60);
+                assert!(input.lines()
+                             .all(|l| l.len() == 60));
+                assert_eq!(input, generate(&SchematicConfig::square(60)));
+
+                let totals = streaming::totals(input.as_bytes())?;
+                assert_eq!(totals.part_numbers, part1_lib::process(&input)?);
+                assert_eq!(totals.part_numbers, part1_lib::process_registers(&input)?);
+                assert_eq!(totals.gear_ratios, part2_lib::process(&input)?);
+                assert_eq!(totals.gear_ratios, part2_lib::process_registers(&input)?);
+                Ok(())
+        }
+}
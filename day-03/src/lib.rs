@@ -3,9 +3,14 @@
 //! And the architecture of the crate will be hierarchical about `part1_lib.rs` and part2_lib.rs`.
 //! (With redundancy common, and by design, between those two files.)
 
+pub mod components;
 pub mod custom_error;
 pub mod data_types_part1;
 pub mod data_types_part2;
+pub mod generate;
+pub mod render;
+pub mod rules;
+pub mod streaming;
 
 pub mod part1_lib;
 pub mod part2_lib;
@@ -1,15 +1,35 @@
 //! Library code for Part 1 of Day 03 of Advent of Code 2023.
 //! `bin > part1.rs` will run this code along with conent of `input1.txt`
 
+use std::io::BufRead;
+
+use aoc_common::grid::Grid;
 use miette::Result;
 use tracing::info;
 
 use crate::{
+        components::Schematic,
         custom_error::AocErrorDay03,
         data_types_part1::{NumberRegister, SpecialAdjacenciesRegister},
+        rules::Rule,
+        streaming,
 };
 
 /// Return sum of values adjacent to special chars
+/// (`Rule::part1` applied to the schematic's adjacency graph.)
+#[tracing::instrument(skip(input))]
+pub fn process(input: &str) -> Result<u64, AocErrorDay03> {
+        let schematic = Schematic::parse(input)?;
+        Ok(Rule::part1().apply(&schematic))
+}
+
+/// Streaming version of `process`: three rows in memory at a time.
+#[tracing::instrument(skip(reader))]
+pub fn process_streaming<R: BufRead>(reader: R) -> Result<u64, AocErrorDay03> {
+        Ok(streaming::totals(reader)?.part_numbers)
+}
+
+/// Register-based version of `process`.
 ///
 /// ## Proposed Flow
 /// - get number locations --> map each to number
@@ -23,17 +43,14 @@ use crate::{
 /// each digit part of a single number)
 /// - ascii input
 #[tracing::instrument]
-pub fn process(input: &str) -> Result<u64, AocErrorDay03> {
+pub fn process_registers(input: &str) -> Result<u64, AocErrorDay03> {
         let mut numbers = NumberRegister::new();
         let mut adjacencies = SpecialAdjacenciesRegister::new();
 
         // register numbers & special chars
-        input.lines()
-             .enumerate()
-             .for_each(|(row, raw_line)| {
-                     numbers.register_numbers(row as i64, raw_line);
-                     adjacencies.register_special_adjacencies(row as i64, raw_line);
-             });
+        let grid: Grid<u8> = input.parse()?;
+        numbers.register_numbers(&grid)?;
+        adjacencies.register_special_adjacencies(&grid);
 
         info!("numbers: {:?}", numbers);
         info!("adjacencies: {:?}", adjacencies);
@@ -53,13 +70,14 @@ pub fn process(input: &str) -> Result<u64, AocErrorDay03> {
 
 #[cfg(test)]
 mod tests {
+        use aoc_common::grid::Pos;
         use indoc::indoc;
 
         use super::*;
 
         #[test]
         fn test_process_example() -> Result<()> {
-                tracing_subscriber::fmt::init();
+                let _ = tracing_subscriber::fmt::try_init();
 
                 let input = indoc! {"
                         467..114..
@@ -78,6 +96,29 @@ mod tests {
                 Ok(())
         }
 
+        #[test]
+        fn test_number_too_big() -> Result<()> {
+                // u64::MAX itself still reads, on every path
+                let input = "18446744073709551615*\n";
+                assert_eq!(process(input)?, u64::MAX);
+                assert_eq!(process_registers(input)?, u64::MAX);
+                assert_eq!(process_streaming(input.as_bytes())?, u64::MAX);
+
+                // one more digit doesn't, on any path
+                let input = ".*...................\n.99999999999999999999\n";
+                for result in [process(input),
+                               process_registers(input),
+                               process_streaming(input.as_bytes())]
+                {
+                        assert!(matches!(result,
+                                         Err(AocErrorDay03::NumberTooBig { at, .. })
+                                         if at == Pos::new(1, 1)),
+                                "{:?}",
+                                result);
+                }
+                Ok(())
+        }
+
         /// This test's expected value is to be populated after
         /// verification of solution.
         /// (useful for future refactors and perfs)
@@ -87,6 +128,8 @@ mod tests {
                 let file_input = include_str!("../input1.txt");
                 let expected = 535351;
                 assert_eq!(process(file_input)?, expected);
+                assert_eq!(process_registers(file_input)?, expected);
+                assert_eq!(process_streaming(file_input.as_bytes())?, expected);
                 Ok(())
         }
 }
@@ -1,32 +1,49 @@
 //! Library code for Part 2 of Day 03 of Advent of Code 2023.
 //! `bin > part2.rs` will run this code along with conent of `input2.txt`
 
-use std::collections::HashMap;
+use std::{collections::HashMap, io::BufRead};
 
+use aoc_common::grid::Grid;
 use miette::Result;
 use tracing::info;
 
 use crate::{
+        components::Schematic,
         custom_error::AocErrorDay03,
         data_types_part2::{NumberRegister, StarAndAdjacenciesRegister},
+        rules::Rule,
+        streaming,
 };
 
+/// Sum of gear ratios: `*` symbols touching exactly two numbers, by their product.
+/// (`Rule::part2` applied to the schematic's adjacency graph.)
+#[tracing::instrument(skip(input))]
+pub fn process(input: &str) -> Result<u64, AocErrorDay03> {
+        let schematic = Schematic::parse(input)?;
+        Ok(Rule::part2().apply(&schematic))
+}
+
+/// Streaming version of `process`: three rows in memory at a time.
+#[tracing::instrument(skip(reader))]
+pub fn process_streaming<R: BufRead>(reader: R) -> Result<u64, AocErrorDay03> {
+        Ok(streaming::totals(reader)?.gear_ratios)
+}
+
+/// Register-based version of `process`.
+///
 /// Basically we need to do the same thing, but now we need
 /// run across a specific special char's adjacencies
 /// Find numbers adjacent. and if precisely two get it's product
 /// (adn then sum or something)
 #[tracing::instrument]
-pub fn process(input: &str) -> Result<u64, AocErrorDay03> {
+pub fn process_registers(input: &str) -> Result<u64, AocErrorDay03> {
         let mut numbers = NumberRegister::new();
         let mut star_adjacencies = StarAndAdjacenciesRegister::new();
 
         // register numbers & special chars
-        input.lines()
-             .enumerate()
-             .for_each(|(row, raw_line)| {
-                     numbers.register_numbers(row as i64, raw_line);
-                     star_adjacencies.register_special_adjacencies(row as i64, raw_line);
-             });
+        let grid: Grid<u8> = input.parse()?;
+        numbers.register_numbers(&grid)?;
+        star_adjacencies.register_special_adjacencies(&grid);
 
         // check what numbers are adjacent to each star
         let mut sum = 0;
@@ -56,7 +73,7 @@ mod tests {
 
         #[test]
         fn test_process_example() -> Result<()> {
-                tracing_subscriber::fmt::init();
+                let _ = tracing_subscriber::fmt::try_init();
 
                 let input = indoc! {"
                         467..114..
@@ -84,6 +101,8 @@ mod tests {
                 let file_input = include_str!("../input2.txt");
                 let expected = 87287096;
                 assert_eq!(process(file_input)?, expected);
+                assert_eq!(process_registers(file_input)?, expected);
+                assert_eq!(process_streaming(file_input.as_bytes())?, expected);
                 Ok(())
         }
 }
@@ -0,0 +1,315 @@
+//! Annotated rendering of a Day 03 schematic, for eyeballing wrong answers.
+//!
+//! Every cell gets a `Class` from the same schematic graph and rules `process` uses,
+//! then the grid is printed with one color per class: ANSI for a terminal, or a standalone HTML page.
+//!
+//! ## Classes
+//! - part numbers (touch a symbol selected by the part rule) vs. numbers that don't
+//! - gears (symbols selected by the gear rule) and the numbers they pair up
+//! - halo: the empty cells around a symbol, i.e. where a digit *would* count
+
+use std::fmt::Write;
+
+use aoc_common::grid::{Grid, Pos};
+
+use crate::{
+        components::{Kind, Label, Schematic},
+        custom_error::AocErrorDay03,
+        rules::Rule,
+};
+
+/// What a cell shows as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Class {
+        Blank,
+        Halo,
+        Number,
+        PartNumber,
+        GearNumber,
+        Symbol,
+        Gear,
+}
+
+impl Class {
+        pub const ALL: [Class; 7] = [Class::Blank,
+                                     Class::Halo,
+                                     Class::Number,
+                                     Class::PartNumber,
+                                     Class::GearNumber,
+                                     Class::Symbol,
+                                     Class::Gear];
+
+        fn ansi(&self) -> &'static str {
+                match self {
+                        Self::Blank => "\x1b[2m",
+                        Self::Halo => "\x1b[2;44m",
+                        Self::Number => "\x1b[31m",
+                        Self::PartNumber => "\x1b[32m",
+                        Self::GearNumber => "\x1b[1;35m",
+                        Self::Symbol => "\x1b[36m",
+                        Self::Gear => "\x1b[1;33m",
+                }
+        }
+
+        fn css_name(&self) -> &'static str {
+                match self {
+                        Self::Blank => "blank",
+                        Self::Halo => "halo",
+                        Self::Number => "number",
+                        Self::PartNumber => "part",
+                        Self::GearNumber => "gear-number",
+                        Self::Symbol => "symbol",
+                        Self::Gear => "gear",
+                }
+        }
+
+        fn css_style(&self) -> &'static str {
+                match self {
+                        Self::Blank => "color: #555",
+                        Self::Halo => "color: #555; background: #1c2a44",
+                        Self::Number => "color: #e06c75",
+                        Self::PartNumber => "color: #98c379",
+                        Self::GearNumber => "color: #c678dd; font-weight: bold",
+                        Self::Symbol => "color: #56b6c2",
+                        Self::Gear => "color: #e5c07b; font-weight: bold",
+                }
+        }
+
+        fn description(&self) -> &'static str {
+                match self {
+                        Self::Blank => "empty",
+                        Self::Halo => "next to a symbol",
+                        Self::Number => "number, not a part",
+                        Self::PartNumber => "part number",
+                        Self::GearNumber => "gear number",
+                        Self::Symbol => "symbol",
+                        Self::Gear => "gear",
+                }
+        }
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// A schematic with every cell classified.
+#[derive(Debug, Clone)]
+pub struct Annotated {
+        text:    Grid<u8>,
+        classes: Grid<Class>,
+}
+
+impl Annotated {
+        /// Classify with the puzzle's own rules: `Rule::part1` for parts, `Rule::part2` for gears.
+        pub fn new(grid: &Grid<u8>) -> Result<Self, AocErrorDay03> {
+                Self::with_rules(grid, &Rule::part1(), &Rule::part2())
+        }
+
+        /// Classify with custom rules.
+        /// Numbers next to a `part_rule` symbol are parts; `gear_rule` symbols are gears.
+        pub fn with_rules(grid: &Grid<u8>,
+                          part_rule: &Rule,
+                          gear_rule: &Rule)
+                          -> Result<Self, AocErrorDay03> {
+                let schematic = Schematic::from_grid(grid)?;
+                let mut classes = grid.map(|_| Class::Blank);
+
+                let mark = |classes: &mut Grid<Class>, label: Label, class: Class| {
+                        for &pos in &schematic.component(label)
+                                              .cells
+                        {
+                                classes[pos] = classes[pos].max(class);
+                        }
+                };
+                for comp in schematic.components() {
+                        match comp.kind {
+                                Kind::Number(_) => mark(&mut classes, comp.label, Class::Number),
+                                Kind::Symbol(_) => {
+                                        mark(&mut classes, comp.label, Class::Symbol);
+                                        for pos in grid.neighbors8(comp.cells[0]) {
+                                                if grid[pos] == b'.' {
+                                                        classes[pos] = Class::Halo;
+                                                }
+                                        }
+                                },
+                        }
+                }
+                for (_, nums) in part_rule.matches(&schematic) {
+                        for l in nums {
+                                mark(&mut classes, l, Class::PartNumber);
+                        }
+                }
+                for (sym, nums) in gear_rule.matches(&schematic) {
+                        mark(&mut classes, sym.label, Class::Gear);
+                        for l in nums {
+                                mark(&mut classes, l, Class::GearNumber);
+                        }
+                }
+                Ok(Self { text: grid.clone(),
+                          classes })
+        }
+
+        pub fn class_at(&self, pos: Pos) -> Option<Class> {
+                self.classes.get(pos).copied()
+        }
+
+        /// Cells per class, in `Class::ALL` order.
+        pub fn counts(&self) -> Vec<(Class, usize)> {
+                Class::ALL.iter()
+                          .map(|&c| {
+                                  (c,
+                                   self.classes
+                                       .iter()
+                                       .filter(|&(_, &k)| k == c)
+                                       .count())
+                          })
+                          .collect()
+        }
+
+        /// One row as runs of same-class text.
+        fn row_spans(&self, r: usize) -> Vec<(Class, String)> {
+                let mut spans: Vec<(Class, String)> = Vec::new();
+                for (&b, &class) in self.text
+                                        .row(r)
+                                        .iter()
+                                        .zip(self.classes.row(r))
+                {
+                        match spans.last_mut() {
+                                Some((c, s)) if *c == class => s.push(b as char),
+                                _ => spans.push((class, (b as char).to_string())),
+                        }
+                }
+                spans
+        }
+
+        fn spans(&self) -> impl Iterator<Item = Vec<(Class, String)>> + '_ {
+                (0..self.text.height()).map(|r| self.row_spans(r))
+        }
+
+        /// Terminal rendering, one escape sequence per run of same-class cells.
+        pub fn to_ansi(&self) -> String {
+                let mut out = String::new();
+                for row in self.spans() {
+                        for (class, text) in row {
+                                out.push_str(class.ansi());
+                                out.push_str(&text);
+                                out.push_str(ANSI_RESET);
+                        }
+                        out.push('\n');
+                }
+                out
+        }
+
+        /// One line per class, colored like the grid.
+        pub fn ansi_legend(&self) -> String {
+                self.counts()
+                    .into_iter()
+                    .fold(String::new(), |mut out, (class, n)| {
+                            let _ = writeln!(out,
+                                             "{}{:>12}{} {:>6} cells  {}",
+                                             class.ansi(),
+                                             class.css_name(),
+                                             ANSI_RESET,
+                                             n,
+                                             class.description());
+                            out
+                    })
+        }
+
+        /// A standalone HTML page: inline stylesheet, legend, and the grid in a `<pre>`.
+        pub fn to_html(&self) -> String {
+                let mut out = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta \
+                                            charset=\"utf-8\">\n<title>Day 03 \
+                                            schematic</title>\n<style>\n");
+                out.push_str("body { background: #1e1e1e; color: #ccc; font-family: monospace; }\n");
+                for class in Class::ALL {
+                        let _ = writeln!(out, ".{} {{ {} }}", class.css_name(), class.css_style());
+                }
+                out.push_str("</style>\n</head>\n<body>\n<ul>\n");
+                for (class, n) in self.counts() {
+                        let _ = writeln!(out,
+                                         "<li><span class=\"{}\">{}</span>: {} cells</li>",
+                                         class.css_name(),
+                                         class.description(),
+                                         n);
+                }
+                out.push_str("</ul>\n<pre>\n");
+                for row in self.spans() {
+                        for (class, text) in row {
+                                let _ = write!(out,
+                                               "<span class=\"{}\">{}</span>",
+                                               class.css_name(),
+                                               escape_html(&text));
+                        }
+                        out.push('\n');
+                }
+                out.push_str("</pre>\n</body>\n</html>\n");
+                out
+        }
+}
+
+fn escape_html(text: &str) -> String {
+        text.chars()
+            .fold(String::with_capacity(text.len()), |mut out, c| {
+                    match c {
+                            '&' => out.push_str("&amp;"),
+                            '<' => out.push_str("&lt;"),
+                            '>' => out.push_str("&gt;"),
+                            '"' => out.push_str("&quot;"),
+                            _ => out.push(c),
+                    }
+                    out
+            })
+}
+
+#[cfg(test)]
+mod tests {
+        use indoc::indoc;
+
+        use super::*;
+
+        const EXAMPLE: &str = indoc! {"
+                467..114..
+                ...*......
+                ..35..633.
+                ......#...
+                617*......
+                .....+.58.
+                ..592.....
+                ......755.
+                ...$.*....
+                .664.598..
+        "};
+
+        #[test]
+        fn test_classes() -> Result<(), AocErrorDay03> {
+                let annotated = Annotated::new(&EXAMPLE.parse()?)?;
+                let at = |r, c| annotated.class_at(Pos::new(r, c));
+                assert_eq!(at(0, 0), Some(Class::GearNumber)); // 467
+                assert_eq!(at(0, 5), Some(Class::Number)); // 114
+                assert_eq!(at(1, 3), Some(Class::Gear));
+                assert_eq!(at(4, 3), Some(Class::Symbol)); // `*` with one neighbor
+                assert_eq!(at(4, 0), Some(Class::PartNumber)); // 617
+                assert_eq!(at(0, 3), Some(Class::Halo));
+                assert_eq!(at(0, 9), Some(Class::Blank));
+                assert_eq!(at(10, 0), None);
+                Ok(())
+        }
+
+        #[test]
+        fn test_outputs_keep_text() -> Result<(), AocErrorDay03> {
+                let annotated = Annotated::new(&"1<&.\n....\n".parse()?)?;
+                let ansi = annotated.to_ansi();
+                let stripped: String = ansi.split("\x1b[")
+                                           .map(|chunk| {
+                                                   chunk.split_once('m')
+                                                        .map_or(chunk, |(_, t)| t)
+                                           })
+                                           .collect();
+                assert_eq!(stripped, "1<&.\n....\n");
+
+                let html = annotated.to_html();
+                assert!(html.contains("<span class=\"part\">1</span><span \
+                                       class=\"symbol\">&lt;&amp;</span>"));
+                assert!(html.ends_with("</html>\n"));
+                Ok(())
+        }
+}
@@ -0,0 +1,280 @@
+//! Symbol rules over a labeled schematic.
+//!
+//! Both parts boil down to: pick some symbols, keep those with the right number of adjacent
+//! numbers, then fold those numbers down to one value.
+//! A `Rule` spells those choices out, so a new schematic variant is a new `Rule`
+//! rather than a new `data_types_partN.rs`.
+//!
+//! ## e.g.
+//! - part 1: any symbol, at least one neighbor, sum of *distinct* adjacent numbers
+//! - part 2: `*` only, exactly two neighbors, product per symbol, summed
+
+use std::{collections::BTreeSet, str::FromStr};
+
+use tracing::trace;
+
+use crate::{
+        components::{Component, Kind, Label, Schematic},
+        custom_error::AocErrorDay03,
+};
+
+/// Which symbol characters a rule looks at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolClass {
+        /// Any symbol at all. (Anything that's neither `.` nor a digit.)
+        Any,
+        /// Only these characters.
+        Only(BTreeSet<u8>),
+        /// Any symbol except these characters.
+        Except(BTreeSet<u8>),
+}
+
+impl SymbolClass {
+        pub fn only(chars: &str) -> Self {
+                Self::Only(chars.bytes().collect())
+        }
+
+        pub fn except(chars: &str) -> Self {
+                Self::Except(chars.bytes().collect())
+        }
+
+        pub fn matches(&self, symbol: u8) -> bool {
+                match self {
+                        Self::Any => true,
+                        Self::Only(set) => set.contains(&symbol),
+                        Self::Except(set) => !set.contains(&symbol),
+                }
+        }
+}
+
+impl FromStr for SymbolClass {
+        type Err = AocErrorDay03;
+
+        /// `any`, `^<chars>` (all but those), or `<chars>` (only those).
+        /// e.g. "any", "*", "^#$"
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                        "" | "^" =>
+                                Err(AocErrorDay03::RuleParse(format!("empty symbol class: {:?}",
+                                                                     s))),
+                        "any" => Ok(Self::Any),
+                        _ => Ok(match s.strip_prefix('^') {
+                                Some(chars) => Self::except(chars),
+                                None => Self::only(s),
+                        }),
+                }
+        }
+}
+
+/// How many adjacent numbers a symbol needs to count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NeighborCount {
+        AtLeast(usize),
+        Exactly(usize),
+        /// inclusive
+        Between(usize, usize),
+}
+
+impl NeighborCount {
+        pub fn allows(&self, n: usize) -> bool {
+                match *self {
+                        Self::AtLeast(lo) => n >= lo,
+                        Self::Exactly(k) => n == k,
+                        Self::Between(lo, hi) => (lo..=hi).contains(&n),
+                }
+        }
+}
+
+impl FromStr for NeighborCount {
+        type Err = AocErrorDay03;
+
+        /// `N` (exactly), `N+` (at least), or `N-M` (between, inclusive).
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let num = |t: &str| {
+                        t.parse::<usize>()
+                         .map_err(|e| {
+                                 AocErrorDay03::RuleParse(format!("neighbor count {:?}: {}", s, e))
+                         })
+                };
+                if let Some(lo) = s.strip_suffix('+') {
+                        Ok(Self::AtLeast(num(lo)?))
+                }
+                else if let Some((lo, hi)) = s.split_once('-') {
+                        Ok(Self::Between(num(lo)?, num(hi)?))
+                }
+                else {
+                        Ok(Self::Exactly(num(s)?))
+                }
+        }
+}
+
+/// How adjacent numbers are folded to one value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reduce {
+        Sum,
+        Product,
+        Max,
+        Count,
+}
+
+impl Reduce {
+        /// Empty input gives the operation's identity (`0` for max and count, `1` for product).
+        pub fn apply(&self, values: impl IntoIterator<Item = u64>) -> u64 {
+                let values = values.into_iter();
+                match self {
+                        Self::Sum => values.sum(),
+                        Self::Product => values.product(),
+                        Self::Max => values.max().unwrap_or(0),
+                        Self::Count => values.count() as u64,
+                }
+        }
+}
+
+impl FromStr for Reduce {
+        type Err = AocErrorDay03;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                        "sum" => Ok(Self::Sum),
+                        "product" => Ok(Self::Product),
+                        "max" => Ok(Self::Max),
+                        "count" => Ok(Self::Count),
+                        _ => Err(AocErrorDay03::RuleParse(format!("unknown reduction: {:?}", s))),
+                }
+        }
+}
+
+/// What the reduction runs over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+        /// Every number next to any qualifying symbol, each counted once, reduced together.
+        DistinctNumbers,
+        /// Each qualifying symbol's numbers reduced on their own; those results summed.
+        PerSymbol,
+}
+
+/// A complete schematic rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+        pub symbols:   SymbolClass,
+        pub neighbors: NeighborCount,
+        pub reduce:    Reduce,
+        pub aggregate: Aggregate,
+}
+
+impl Rule {
+        /// Sum of part numbers.
+        pub fn part1() -> Self {
+                Self { symbols:   SymbolClass::Any,
+                       neighbors: NeighborCount::AtLeast(1),
+                       reduce:    Reduce::Sum,
+                       aggregate: Aggregate::DistinctNumbers, }
+        }
+
+        /// Sum of gear ratios.
+        pub fn part2() -> Self {
+                Self { symbols:   SymbolClass::only("*"),
+                       neighbors: NeighborCount::Exactly(2),
+                       reduce:    Reduce::Product,
+                       aggregate: Aggregate::PerSymbol, }
+        }
+
+        /// Symbols this rule selects, with the labels of their adjacent numbers.
+        pub fn matches<'s>(&'s self,
+                           schematic: &'s Schematic)
+                           -> impl Iterator<Item = (&'s Component, Vec<Label>)> + 's {
+                schematic.components()
+                         .iter()
+                         .filter(|c| matches!(c.kind, Kind::Symbol(s) if self.symbols.matches(s)))
+                         .filter_map(|c| {
+                                 let nums: Vec<Label> = schematic.neighbors(c.label)
+                                                                 .filter(|n| n.number().is_some())
+                                                                 .map(|n| n.label)
+                                                                 .collect();
+                                 self.neighbors
+                                     .allows(nums.len())
+                                     .then_some((c, nums))
+                         })
+        }
+
+        pub fn apply(&self, schematic: &Schematic) -> u64 {
+                let value = |l: &Label| {
+                        schematic.component(*l)
+                                 .number()
+                                 .expect("only numbers are collected")
+                };
+                match self.aggregate {
+                        Aggregate::DistinctNumbers => {
+                                let distinct: BTreeSet<Label> = self.matches(schematic)
+                                                                    .flat_map(|(_, nums)| nums)
+                                                                    .collect();
+                                self.reduce
+                                    .apply(distinct.iter().map(value))
+                        },
+                        Aggregate::PerSymbol =>
+                                self.matches(schematic)
+                                    .map(|(sym, nums)| {
+                                            let v = self.reduce
+                                                        .apply(nums.iter().map(value));
+                                            trace!(bbox = ?sym.bbox, v);
+                                            v
+                                    })
+                                    .sum(),
+                }
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use indoc::indoc;
+
+        use super::*;
+
+        const EXAMPLE: &str = indoc! {"
+                467..114..
+                ...*......
+                ..35..633.
+                ......#...
+                617*......
+                .....+.58.
+                ..592.....
+                ......755.
+                ...$.*....
+                .664.598..
+        "};
+
+        #[test]
+        fn test_presets_match_puzzle() -> Result<(), AocErrorDay03> {
+                let schematic = Schematic::parse(EXAMPLE)?;
+                assert_eq!(Rule::part1().apply(&schematic), 4361);
+                assert_eq!(Rule::part2().apply(&schematic), 467835);
+                Ok(())
+        }
+
+        #[test]
+        fn test_variants() -> Result<(), AocErrorDay03> {
+                let schematic = Schematic::parse(EXAMPLE)?;
+                // how many `*` touch at least one number
+                let stars = Rule { symbols:   "*".parse()?,
+                                   neighbors: "1+".parse()?,
+                                   reduce:    Reduce::Count,
+                                   aggregate: Aggregate::PerSymbol, };
+                assert_eq!(stars.matches(&schematic)
+                                .count(),
+                           3);
+                // biggest neighbor of each non-`*` symbol, summed: # 633, + 592, $ 664
+                let maxes = Rule { symbols:   "^*".parse()?,
+                                   neighbors: "1-8".parse()?,
+                                   reduce:    "max".parse()?,
+                                   aggregate: Aggregate::PerSymbol, };
+                assert_eq!(maxes.apply(&schematic), 633 + 592 + 664);
+
+                assert!("".parse::<SymbolClass>()
+                          .is_err());
+                assert!("two".parse::<NeighborCount>()
+                             .is_err());
+                assert!("mean".parse::<Reduce>()
+                              .is_err());
+                Ok(())
+        }
+}
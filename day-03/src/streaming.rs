@@ -0,0 +1,276 @@
+//! Bounded-memory Day 03: a sliding three-row window over a `BufRead`.
+//!
+//! Everything a row's numbers or gears can touch sits in the row itself and its two neighbors.
+//! So once the row *below* is read, the row is complete: its part numbers and gear ratios are
+//! reported and the row above it is dropped.
+//! Memory is three line buffers, whatever the height of the schematic.
+//!
+//! ## Reporting
+//! - a number is reported with the row it sits on
+//! - a gear ratio is reported with the row its `*` sits on
+
+use std::io::BufRead;
+
+use aoc_common::grid::{GridError, Pos};
+use tracing::trace;
+
+use crate::{components::read_number, custom_error::AocErrorDay03};
+
+/// What a completed row contributed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RowReport {
+        pub row:          usize,
+        pub part_numbers: Vec<u64>,
+        pub gear_ratios:  Vec<u64>,
+}
+
+/// Both answers, accumulated over a stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Totals {
+        pub part_numbers: u64,
+        pub gear_ratios:  u64,
+}
+
+/// Iterator of `RowReport`s, one per input row, in order.
+#[derive(Debug)]
+pub struct RowStream<R> {
+        reader:  R,
+        /// above, current, below; an empty buffer is "no row"
+        window:  [Vec<u8>; 3],
+        /// each window row's numbers, as `(start, end, value)`, left to right
+        numbers: [Vec<(usize, usize, u64)>; 3],
+        /// index of `window[1]`
+        row:     usize,
+        width:   Option<usize>,
+        eof:     bool,
+}
+
+/// Stream a schematic row by row.
+pub fn rows<R: BufRead>(reader: R) -> RowStream<R> {
+        RowStream { reader,
+                    window: Default::default(),
+                    numbers: Default::default(),
+                    row: 0,
+                    width: None,
+                    eof: false }
+}
+
+/// Sum both answers in one pass.
+pub fn totals<R: BufRead>(reader: R) -> Result<Totals, AocErrorDay03> {
+        let mut totals = Totals::default();
+        for report in rows(reader) {
+                let report = report?;
+                totals.part_numbers += report.part_numbers
+                                             .iter()
+                                             .sum::<u64>();
+                totals.gear_ratios += report.gear_ratios
+                                            .iter()
+                                            .sum::<u64>();
+        }
+        Ok(totals)
+}
+
+impl<R: BufRead> RowStream<R> {
+        /// Read the next non-blank line into `window[slot]`, without its line ending.
+        /// (Blank lines are skipped, as `Grid::from_str` skips them.)
+        /// Leaves the slot empty at end of input.
+        fn fill(&mut self, slot: usize, row: usize) -> Result<(), AocErrorDay03> {
+                let buf = &mut self.window[slot];
+                buf.clear();
+                self.numbers[slot].clear();
+                while buf.is_empty() {
+                        let read = if self.eof {
+                                0
+                        }
+                        else {
+                                self.reader
+                                    .read_until(b'\n', buf)?
+                        };
+                        if read == 0 {
+                                self.eof = true;
+                                return Ok(());
+                        }
+                        while matches!(buf.last(), Some(b'\n' | b'\r')) {
+                                buf.pop();
+                        }
+                }
+                let expected = *self.width
+                                    .get_or_insert(buf.len());
+                if buf.len() != expected {
+                        return Err(GridError::Ragged { row,
+                                                       expected,
+                                                       found: buf.len() }.into());
+                }
+                for number in numbers(buf, row) {
+                        self.numbers[slot].push(number?);
+                }
+                Ok(())
+        }
+
+        fn report(&self) -> RowReport {
+                let [above, current, below] = &self.window;
+                let neighborhood = [above.as_slice(), current, below];
+
+                let part_numbers = self.numbers[1].iter()
+                                                  .filter(|&&(start, end, _)| {
+                                                          touches_symbol(&neighborhood, start, end)
+                                                  })
+                                                  .map(|&(_, _, val)| val)
+                                                  .collect();
+                let gear_ratios = current.iter()
+                                         .enumerate()
+                                         .filter(|&(_, &b)| b == b'*')
+                                         .filter_map(|(col, _)| gear_ratio(&self.numbers, col))
+                                         .collect();
+                RowReport { row: self.row,
+                            part_numbers,
+                            gear_ratios }
+        }
+}
+
+impl<R: BufRead> Iterator for RowStream<R> {
+        type Item = Result<RowReport, AocErrorDay03>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+                if self.width.is_none() {
+                        // first call: nothing above, the current row still to read
+                        if let Err(e) = self.fill(1, 0) {
+                                return Some(Err(e));
+                        }
+                }
+                if self.window[1].is_empty() {
+                        return None;
+                }
+                if let Err(e) = self.fill(2, self.row + 1) {
+                        self.eof = true;
+                        self.window[1].clear();
+                        return Some(Err(e));
+                }
+                let report = self.report();
+                trace!(?report);
+                self.window.rotate_left(1);
+                self.numbers.rotate_left(1);
+                self.row += 1;
+                Some(Ok(report))
+        }
+}
+
+fn is_symbol(b: u8) -> bool {
+        b != b'.' && !b.is_ascii_digit()
+}
+
+/// Whether any cell around the number `start..end` holds a symbol.
+fn touches_symbol(neighborhood: &[&[u8]; 3], start: usize, end: usize) -> bool {
+        neighborhood.iter()
+                    .flat_map(|row| span(row, start, end))
+                    .any(|&b| is_symbol(b))
+}
+
+/// Product of the numbers around a `*` in column `col`, if there are exactly two.
+/// (Each row's numbers are sorted, so only the few near `col` are looked at.)
+fn gear_ratio(numbers: &[Vec<(usize, usize, u64)>; 3], col: usize) -> Option<u64> {
+        let mut touching =
+                numbers.iter()
+                       .flat_map(|row| {
+                               let first = row.partition_point(|&(_, end, _)| end < col);
+                               row[first..].iter()
+                                           .take_while(move |&&(start, _, _)| start <= col + 1)
+                       });
+        match (touching.next(), touching.next(), touching.next()) {
+                (Some(a), Some(b), None) => Some(a.2 * b.2),
+                _ => None,
+        }
+}
+
+/// Cells `start - 1 ..= end` of a row, clipped to the row. (`end` exclusive, as for numbers.)
+fn span(row: &[u8], start: usize, end: usize) -> &[u8] {
+        if row.is_empty() {
+                return row;
+        }
+        &row[start.saturating_sub(1)..(end + 1).min(row.len())]
+}
+
+/// `(start, end, value)` of every number in row `row_index`, `end` exclusive.
+fn numbers(row: &[u8],
+           row_index: usize)
+           -> impl Iterator<Item = Result<(usize, usize, u64), AocErrorDay03>> + '_ {
+        let mut col = 0;
+        std::iter::from_fn(move || {
+                while col < row.len() && !row[col].is_ascii_digit() {
+                        col += 1;
+                }
+                let start = col;
+                while col < row.len() && row[col].is_ascii_digit() {
+                        col += 1;
+                }
+                (start < col).then(|| {
+                                     let val = read_number(&row[start..col],
+                                                           Pos::new(row_index, start))?;
+                                     Ok((start, col, val))
+                             })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+        use indoc::indoc;
+
+        use super::*;
+
+        const EXAMPLE: &str = indoc! {"
+                467..114..
+                ...*......
+                ..35..633.
+                ......#...
+                617*......
+                .....+.58.
+                ..592.....
+                ......755.
+                ...$.*....
+                .664.598..
+        "};
+
+        #[test]
+        fn test_row_reports() -> Result<(), AocErrorDay03> {
+                let reports = rows(EXAMPLE.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+                assert_eq!(reports.len(), 10);
+                assert_eq!(reports[0].part_numbers, vec![467]);
+                assert_eq!(reports[1].gear_ratios, vec![467 * 35]);
+                assert_eq!(reports[5].part_numbers, vec![]);
+                assert_eq!(reports[8].gear_ratios, vec![755 * 598]);
+                assert_eq!(totals(EXAMPLE.as_bytes())?, Totals { part_numbers: 4361,
+                                                                 gear_ratios:  467835, });
+                Ok(())
+        }
+
+        #[test]
+        fn test_ragged_and_crlf() {
+                assert!(matches!(totals("1*.\r\n..2\r\n".as_bytes()),
+                                 Ok(Totals { part_numbers: 3,
+                                             .. })));
+                let err = rows("...\n..\n".as_bytes()).find_map(Result::err);
+                assert!(matches!(err,
+                                 Some(AocErrorDay03::Grid(GridError::Ragged { row:      1,
+                                                                              expected: 3,
+                                                                              found:    2, }))));
+        }
+
+        #[test]
+        fn test_blank_lines_like_grid() -> Result<(), AocErrorDay03> {
+                for input in ["467..\n...*.\n\n",
+                              "\n467..\n\n...*.\r\n\r\n..35.\n",
+                              &EXAMPLE.replace('\n', "\n\n")]
+                {
+                        let streamed = totals(input.as_bytes())?;
+                        assert_eq!(streamed.part_numbers,
+                                   crate::part1_lib::process(input)?,
+                                   "{:?}",
+                                   input);
+                        assert_eq!(streamed.gear_ratios,
+                                   crate::part2_lib::process(input)?,
+                                   "{:?}",
+                                   input);
+                }
+                Ok(())
+        }
+}
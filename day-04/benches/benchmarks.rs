@@ -1,8 +1,14 @@
 //! Benchmarking code for use with **divan** crate.
 //! Specifies two functions, corresponding to two parts of the
 //! Day 04 of Advent of Code 2023 problem.
+//! (Plus match counting alone, on an already parsed pile: scan vs. bitsets vs. packed bitsets.)
 
-use day_04::*;
+use day_04::{
+        bitset::{CardBits, PackedPile},
+        scratch_card::{CardPile, ScratchCard},
+        *,
+};
+use divan::Bencher;
 
 fn main() {
         // Run registered benchmarks.
@@ -18,3 +24,47 @@ fn part1() {
 fn part2() {
         part2_lib::process(divan::black_box(include_str!("../input2.txt",))).unwrap();
 }
+
+#[divan::bench]
+fn part1_bitset() {
+        part1_lib::process_bitset(divan::black_box(include_str!("../input1.txt",))).unwrap();
+}
+
+fn pile() -> CardPile {
+        include_str!("../input1.txt").parse()
+                                     .unwrap()
+}
+
+#[divan::bench]
+fn matches_scan(bencher: Bencher) {
+        let pile = pile();
+        bencher.bench(|| {
+                       divan::black_box(&pile).cards()
+                                              .iter()
+                                              .map(ScratchCard::matches)
+                                              .sum::<u64>()
+               });
+}
+
+#[divan::bench]
+fn matches_bitset(bencher: Bencher) {
+        let bits: Vec<CardBits> = pile().cards()
+                                        .iter()
+                                        .map(|c| CardBits::try_from(c).unwrap())
+                                        .collect();
+        bencher.bench(|| {
+                       divan::black_box(&bits).iter()
+                                              .map(CardBits::matches)
+                                              .sum::<u64>()
+               });
+}
+
+#[divan::bench]
+fn matches_packed(bencher: Bencher) {
+        let packed = PackedPile::try_from(&pile()).unwrap();
+        let mut out = vec![0; packed.len()];
+        bencher.bench_local(|| {
+                       divan::black_box(&packed).matches_into(&mut out);
+                       out.iter().sum::<u64>()
+               });
+}
@@ -0,0 +1,64 @@
+//! Explain CLI for Day 04 of Advent of Code 2023.
+//! Shows how part 2's copies cascade through `input2.txt` (or `--input <path>`).
+//!
+//! ## Usage
+//! ```text
+//! explain [--input <path>]          table: wins, copies, and which cards gave them
+//! explain [--input <path>] --dot    DOT graph of the cascade
+//! ```
+//! Either also takes `--overflow <clamp|error|wrap>`, for wins past the bottom of the pile.
+
+use day_04::{
+        cascade::{explain_with, Overflow},
+        scratch_card::CardPile,
+};
+use miette::{miette, Context, IntoDiagnostic, Result};
+
+const USAGE: &str = "usage: explain [--input <path>] [--overflow <clamp|error|wrap>] [--dot]";
+
+/// Value following `flag`, removed from `args` along with the flag.
+fn take_flag(args: &mut Vec<String>, flag: &str) -> Result<Option<String>> {
+        let Some(i) = args.iter()
+                          .position(|a| a == flag)
+        else {
+                return Ok(None);
+        };
+        let value = args.get(i + 1)
+                        .ok_or_else(|| miette!("{} needs a value\n{}", flag, USAGE))?
+                        .clone();
+        args.drain(i..=i + 1);
+        Ok(Some(value))
+}
+
+fn main() -> Result<()> {
+        tracing_subscriber::fmt::init();
+
+        let mut args: Vec<String> = std::env::args().skip(1)
+                                                    .collect();
+        let input = match take_flag(&mut args, "--input")? {
+                Some(path) =>
+                        std::fs::read_to_string(&path).into_diagnostic()
+                                                      .wrap_err_with(|| format!("read {}", path))?,
+                None => include_str!("../../input2.txt").to_string(),
+        };
+        let overflow = match take_flag(&mut args, "--overflow")?.as_deref() {
+                None | Some("clamp") => Overflow::Clamp,
+                Some("error") => Overflow::Error,
+                Some("wrap") => Overflow::Wrap,
+                Some(other) => return Err(miette!("unknown overflow mode {:?}\n{}", other, USAGE)),
+        };
+        let pile: CardPile = input.parse()
+                                  .context("parse card pile")?;
+        let cascade = explain_with(&pile, overflow).context("run cascade")?;
+
+        match args.iter()
+                  .map(String::as_str)
+                  .collect::<Vec<_>>()
+                  .as_slice()
+        {
+                [] => print!("{}", cascade.to_table()),
+                ["--dot"] => print!("{}", cascade.to_dot()),
+                _ => return Err(miette!("{}", USAGE)),
+        }
+        Ok(())
+}
@@ -0,0 +1,211 @@
+//! Bitset matching: card numbers are below 100, so a side of a card fits in one `u128`.
+//!
+//! Matching numbers are then `(winning & haves).count_ones()`: one AND and one popcount
+//! instead of a scan of `winning` per held number.
+//! `PackedPile` lays a whole pile out as two flat `u128` columns, so the overlap runs
+//! as one tight loop the compiler can unroll and vectorize.
+//!
+//! ## Caveat
+//! A set holds each number once. If a card *held* the same number twice,
+//! the scan (`ScratchCard::matches`) would count it twice and the bitset once.
+//! (Puzzle cards never repeat a number on either side.)
+
+use tracing::debug;
+
+use crate::{
+        custom_error::AocErrorDay04,
+        scratch_card::{CardPile, ScratchCard},
+};
+
+/// Set of card numbers in `0..=127`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NumberSet(u128);
+
+impl NumberSet {
+        pub const MAX: u64 = 127;
+
+        pub fn new() -> Self {
+                Self(0)
+        }
+
+        pub fn bits(&self) -> u128 {
+                self.0
+        }
+
+        /// Add a number; `Ok(false)` if it was already there.
+        pub fn insert(&mut self, n: u64) -> Result<bool, AocErrorDay04> {
+                if n > Self::MAX {
+                        return Err(AocErrorDay04::NumberOutOfRange { value: n,
+                                                                     max:   Self::MAX, });
+                }
+                let bit = 1u128 << n;
+                let fresh = self.0 & bit == 0;
+                self.0 |= bit;
+                Ok(fresh)
+        }
+
+        pub fn contains(&self, n: u64) -> bool {
+                n <= Self::MAX && self.0 & (1u128 << n) != 0
+        }
+
+        pub fn len(&self) -> u32 {
+                self.0.count_ones()
+        }
+
+        pub fn is_empty(&self) -> bool {
+                self.0 == 0
+        }
+
+        /// How many numbers are in both sets.
+        pub fn overlap(&self, other: &Self) -> u32 {
+                (self.0 & other.0).count_ones()
+        }
+}
+
+impl TryFrom<&[u64]> for NumberSet {
+        type Error = AocErrorDay04;
+
+        fn try_from(nums: &[u64]) -> Result<Self, Self::Error> {
+                let mut set = Self::new();
+                for &n in nums {
+                        set.insert(n)?;
+                }
+                Ok(set)
+        }
+}
+
+/// Both sides of a card as bitsets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CardBits {
+        pub winning: NumberSet,
+        pub haves:   NumberSet,
+}
+
+impl CardBits {
+        pub fn matches(&self) -> u64 {
+                u64::from(self.winning
+                              .overlap(&self.haves))
+        }
+}
+
+impl TryFrom<&ScratchCard> for CardBits {
+        type Error = AocErrorDay04;
+
+        fn try_from(card: &ScratchCard) -> Result<Self, Self::Error> {
+                Ok(Self { winning: card.winning
+                                       .as_slice()
+                                       .try_into()?,
+                          haves:   card.haves
+                                       .as_slice()
+                                       .try_into()?, })
+        }
+}
+
+/// A pile as two columns of bitsets: card `i` is `winning[i]` & `haves[i]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackedPile {
+        winning: Vec<u128>,
+        haves:   Vec<u128>,
+}
+
+impl PackedPile {
+        /// Cards processed per step of `matches_into`.
+        const LANES: usize = 8;
+
+        pub fn len(&self) -> usize {
+                self.winning.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+                self.winning.is_empty()
+        }
+
+        /// Matching numbers per card, in pile order.
+        pub fn matches(&self) -> Vec<u64> {
+                let mut out = vec![0; self.len()];
+                self.matches_into(&mut out);
+                out
+        }
+
+        /// Like `matches`, into a caller's buffer (which must be `len()` long).
+        /// Runs `LANES` cards at a time, with no branches in the loop body.
+        pub fn matches_into(&self, out: &mut [u64]) {
+                assert_eq!(out.len(),
+                           self.len(),
+                           "output buffer must match pile length");
+                let (w_chunks, w_rest) = self.winning
+                                             .as_chunks::<{ Self::LANES }>();
+                let (h_chunks, h_rest) = self.haves
+                                             .as_chunks::<{ Self::LANES }>();
+                let (o_chunks, o_rest) = out.as_chunks_mut::<{ Self::LANES }>();
+                for ((w, h), o) in w_chunks.iter()
+                                           .zip(h_chunks)
+                                           .zip(o_chunks)
+                {
+                        for lane in 0..Self::LANES {
+                                o[lane] = u64::from((w[lane] & h[lane]).count_ones());
+                        }
+                }
+                for ((w, h), o) in w_rest.iter()
+                                         .zip(h_rest)
+                                         .zip(o_rest)
+                {
+                        *o = u64::from((w & h).count_ones());
+                }
+        }
+}
+
+impl TryFrom<&CardPile> for PackedPile {
+        type Error = AocErrorDay04;
+
+        fn try_from(pile: &CardPile) -> Result<Self, Self::Error> {
+                let mut packed = Self { winning: Vec::with_capacity(pile.cards().len()),
+                                        haves:   Vec::with_capacity(pile.cards().len()), };
+                for card in pile.cards() {
+                        let bits = CardBits::try_from(card)?;
+                        packed.winning
+                              .push(bits.winning.bits());
+                        packed.haves
+                              .push(bits.haves.bits());
+                }
+                debug!(cards = packed.len(), "pile packed");
+                Ok(packed)
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use super::*;
+
+        #[test]
+        fn test_number_set() -> Result<(), AocErrorDay04> {
+                let mut set = NumberSet::new();
+                assert!(set.insert(0)?);
+                assert!(set.insert(127)?);
+                assert!(!set.insert(127)?);
+                assert!(set.contains(127) && !set.contains(128));
+                assert_eq!(set.len(), 2);
+                let too_big = set.insert(128);
+                assert!(matches!(too_big, Err(AocErrorDay04::NumberOutOfRange { .. })));
+
+                let other = NumberSet::try_from(&[0, 5, 127][..])?;
+                assert_eq!(set.overlap(&other), 2);
+                Ok(())
+        }
+
+        #[test]
+        fn test_bitsets_agree_with_scan() -> Result<(), AocErrorDay04> {
+                let pile: CardPile = include_str!("../input1.txt").parse()?;
+                let scanned: Vec<u64> = pile.cards()
+                                            .iter()
+                                            .map(ScratchCard::matches)
+                                            .collect();
+                let per_card = pile.cards()
+                                   .iter()
+                                   .map(|c| CardBits::try_from(c).map(|b| b.matches()))
+                                   .collect::<Result<Vec<u64>, _>>()?;
+                assert_eq!(per_card, scanned);
+                assert_eq!(PackedPile::try_from(&pile)?.matches(), scanned);
+                Ok(())
+        }
+}
@@ -0,0 +1,219 @@
+//! Explaining part 2: how copies cascade down a pile.
+//!
+//! Same top-down pass as `part2_lib::process`, but each card also records who gave it copies.
+//! Card `i` with `w` wins and `c` copies gives `c` copies to each of the next `w` cards,
+//! so every contribution comes from a card above.
+//! The result prints as a table, or as a DOT graph (edges labeled with copies given).
+//!
+//! ## Bottom of the pile
+//! The puzzle promises no card wins past the last one. `Overflow` says what to do if one does.
+//!
+//! ## e.g.
+//! ```text
+//! explain --input custom.txt --dot | dot -Tsvg > cascade.svg
+//! ```
+
+use std::fmt::Write;
+
+use crate::{
+        custom_error::AocErrorDay04,
+        scratch_card::{CardPile, ScratchCard},
+};
+
+/// What happens to wins that reach past the bottom of the pile.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overflow {
+        /// Drop them: there are no cards there.
+        #[default]
+        Clamp,
+        /// Refuse the pile.
+        Error,
+        /// Carry on from the top of the pile.
+        /// (Those cards were already played, so their extra copies don't cascade any further.)
+        /// At most one lap: a card never wins itself, or the same card twice.
+        Wrap,
+}
+
+impl Overflow {
+        /// Positions of the cards won by the card at `loc`, in a pile of `len`.
+        pub fn targets(&self,
+                       card: &ScratchCard,
+                       loc: usize,
+                       len: usize)
+                       -> Result<impl Iterator<Item = usize>, AocErrorDay04> {
+                let wins = card.matches() as usize;
+                let remaining = len - loc - 1;
+                let reach = match self {
+                        _ if wins <= remaining => wins,
+                        Self::Clamp => remaining,
+                        Self::Error =>
+                                return Err(AocErrorDay04::CascadeOverflow { id: card.id,
+                                                                            wins: card.matches(),
+                                                                            remaining }),
+                        Self::Wrap => wins.min(len - 1),
+                };
+                Ok((1..=reach).map(move |k| (loc + k) % len))
+        }
+}
+
+/// One card's part in the cascade.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardStep {
+        pub id:            u64,
+        /// matching numbers on the card
+        pub wins:          u64,
+        /// final number of copies (the original included)
+        pub copies:        u64,
+        /// `(card id, copies given)` for every earlier card that gave this one copies
+        pub contributions: Vec<(u64, u64)>,
+}
+
+/// Every card's step, in pile order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cascade {
+        pub steps: Vec<CardStep>,
+}
+
+/// Run the cascade, keeping track of contributions.
+/// Wins past the bottom of the pile are dropped (`Overflow::Clamp`).
+pub fn explain(pile: &CardPile) -> Cascade {
+        explain_with(pile, Overflow::Clamp).expect("clamping never fails")
+}
+
+/// Run the cascade, handling the bottom of the pile as asked.
+pub fn explain_with(pile: &CardPile, overflow: Overflow) -> Result<Cascade, AocErrorDay04> {
+        let mut steps: Vec<CardStep> = pile.cards()
+                                           .iter()
+                                           .map(|card| CardStep { id:            card.id,
+                                                                  wins:          card.matches(),
+                                                                  copies:        1,
+                                                                  contributions: Vec::new(), })
+                                           .collect();
+        for (loc, card) in pile.cards()
+                               .iter()
+                               .enumerate()
+        {
+                let (id, copies) = (steps[loc].id, steps[loc].copies);
+                for target in overflow.targets(card, loc, steps.len())? {
+                        steps[target].copies += copies;
+                        steps[target].contributions
+                                     .push((id, copies));
+                }
+        }
+        Ok(Cascade { steps })
+}
+
+impl Cascade {
+        /// Total cards won, originals included. (The part 2 answer.)
+        pub fn total(&self) -> u64 {
+                self.steps
+                    .iter()
+                    .map(|s| s.copies)
+                    .sum()
+        }
+
+        /// Plain text table, one card per row.
+        pub fn to_table(&self) -> String {
+                let mut out = format!("{:>6} {:>5} {:>12}  copies from\n",
+                                      "card", "wins", "copies");
+                for step in &self.steps {
+                        let from = step.contributions
+                                       .iter()
+                                       .map(|(id, n)| format!("{}(+{})", id, n))
+                                       .collect::<Vec<_>>()
+                                       .join(" ");
+                        let row = format!("{:>6} {:>5} {:>12}  {}",
+                                          step.id, step.wins, step.copies, from);
+                        let _ = writeln!(out, "{}", row.trim_end());
+                }
+                let _ = writeln!(out, "total: {}", self.total());
+                out
+        }
+
+        /// DOT graph: a node per card (with wins and copies), an edge per contribution.
+        pub fn to_dot(&self) -> String {
+                let mut out = String::from("digraph cascade {\n");
+                out.push_str("        rankdir=LR;\n");
+                out.push_str("        node [shape=box];\n");
+                for step in &self.steps {
+                        let _ = writeln!(out,
+                                         "        c{} [label=\"Card {}\\nwins {}\\ncopies {}\"];",
+                                         step.id, step.id, step.wins, step.copies);
+                }
+                for step in &self.steps {
+                        for (from, n) in &step.contributions {
+                                let _ = writeln!(out,
+                                                 "        c{} -> c{} [label=\"+{}\"];",
+                                                 from, step.id, n);
+                        }
+                }
+                out.push_str("}\n");
+                out
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use indoc::indoc;
+
+        use super::*;
+        use crate::custom_error::AocErrorDay04;
+
+        const EXAMPLE: &str = indoc! {"
+                Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+                Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+                Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+                Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+                Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+                Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+        "};
+
+        #[test]
+        fn test_example_cascade() -> Result<(), AocErrorDay04> {
+                let cascade = explain(&EXAMPLE.parse()?);
+                let copies: Vec<u64> = cascade.steps
+                                              .iter()
+                                              .map(|s| s.copies)
+                                              .collect();
+                assert_eq!(copies, vec![1, 2, 4, 8, 14, 1]);
+                assert_eq!(cascade.total(), 30);
+                assert_eq!(cascade.steps[3].contributions, vec![(1, 1), (2, 2), (3, 4)]);
+
+                assert!(cascade.to_table()
+                               .contains("total: 30"));
+                let dot = cascade.to_dot();
+                assert!(dot.contains("c3 -> c4 [label=\"+4\"];"));
+                assert_eq!(dot.matches("->").count(),
+                           cascade.steps
+                                  .iter()
+                                  .map(|s| s.contributions.len())
+                                  .sum::<usize>());
+                Ok(())
+        }
+
+        #[test]
+        fn test_overflow_modes() -> Result<(), AocErrorDay04> {
+                // card 2 wins 2, with only card 3 below it
+                let pile: CardPile =
+                        "Card 1: 1 2 | 1 9\nCard 2: 1 2 | 1 2\nCard 3: 1 2 | 8 9\n".parse()?;
+                assert_eq!(explain(&pile).total(), 1 + 2 + 3);
+
+                let wrapped = explain_with(&pile, Overflow::Wrap)?;
+                assert_eq!(wrapped.steps[0].copies, 3);
+                assert_eq!(wrapped.steps[0].contributions, vec![(2, 2)]);
+                assert_eq!(wrapped.total(), 3 + 2 + 3);
+
+                assert!(matches!(explain_with(&pile, Overflow::Error),
+                                 Err(AocErrorDay04::CascadeOverflow { id:        2,
+                                                                      wins:      2,
+                                                                      remaining: 1, })));
+
+                // card 1 wins 3 in a pile of 2: one lap reaches card 2 once, and never card 1
+                let short: CardPile = "Card 1: 1 2 3 | 1 2 3\nCard 2: 4 5 6 | 7 8 9\n".parse()?;
+                let wrapped = explain_with(&short, Overflow::Wrap)?;
+                assert_eq!(wrapped.steps[0].contributions, vec![]);
+                assert_eq!(wrapped.steps[1].contributions, vec![(1, 1)]);
+                assert_eq!(wrapped.total(), 1 + 2);
+                Ok(())
+        }
+}
@@ -1,11 +1,86 @@
 //! Custom Error type for Day 04 of Advent of Code 2023.
 
+use std::fmt;
+
+use aoc_common::{patterns::PatternError, spanned::InvalidInput};
 use miette::Diagnostic;
 use thiserror::Error;
 
+use crate::scratch_card::Widths;
+
 #[derive(Error, Diagnostic, Debug)]
 pub enum AocErrorDay04 {
         #[error(transparent)]
         #[diagnostic(code(aoc::io_error))]
         IoError(#[from] std::io::Error),
+
+        #[error(transparent)]
+        #[diagnostic(transparent)]
+        Pattern(#[from] PatternError),
+
+        #[error(transparent)]
+        #[diagnostic(transparent)]
+        InvalidCard(Box<InvalidCard>),
+
+        #[error("No cards in pile")]
+        #[diagnostic(code(aoc::empty_pile))]
+        EmptyPile,
+
+        #[error("Card number {value} is too big for a bitset (max {max})")]
+        #[diagnostic(code(aoc::number_out_of_range))]
+        NumberOutOfRange { value: u64, max: u64 },
+
+        #[error("Pile scores more points than fit in a u64")]
+        #[diagnostic(code(aoc::score_overflow),
+                     help("a card with n matches scores 2^(n-1), so 65 matches is already too many"))]
+        ScoreOverflow,
+
+        #[error("Card {id} wins {wins} cards, but only {remaining} are left below it")]
+        #[diagnostic(code(aoc::cascade_overflow),
+                     help("use `Overflow::Clamp` or `Overflow::Wrap` to allow this"))]
+        CascadeOverflow {
+                id:        u64,
+                wins:      u64,
+                remaining: usize,
+        },
+}
+
+/// A card in a pile that can't be read, or doesn't fit with the cards before it.
+pub type InvalidCard = InvalidInput<CardProblem>;
+
+impl From<InvalidCard> for AocErrorDay04 {
+        fn from(err: InvalidCard) -> Self {
+                Self::InvalidCard(Box::new(err))
+        }
+}
+
+/// What's wrong with an `InvalidCard`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardProblem {
+        /// Not `Card <id>: <numbers> | <numbers>`, or a number that won't parse.
+        Unreadable(String),
+        Width {
+                found:    Widths,
+                expected: Widths,
+        },
+        /// Ids must count up by one from the first card.
+        Id {
+                found:    u64,
+                expected: u64,
+        },
+}
+
+impl fmt::Display for CardProblem {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                        Self::Unreadable(reason) => write!(f, "{}", reason),
+                        Self::Width { found, expected } => {
+                                write!(f,
+                                       "widths {}, but the pile started with {}",
+                                       found, expected)
+                        },
+                        Self::Id { found, expected } =>
+                                write!(f, "id {}, expected {}", found, expected),
+                }
+        }
 }
@@ -1,122 +1,41 @@
 //! Library code for Part 1 of Day 04 of Advent of Code 2023.
 //! `bin > part1.rs` will run this code along with conent of `input1.txt`
 
-use std::iter::FromIterator;
-
-use derive_more::{Constructor, IntoIterator};
 use miette::Result;
-use once_cell::sync::Lazy;
-use regex::Regex;
 use tracing::info;
 
-use crate::custom_error::AocErrorDay04;
-
-// // NOTE: these regex are overlapping
-// // If one decides to pick of elements of athe line for PERF
-// // then the borders will need to change
-// const CARD_NUM: &str = r"Card (\d+):"; // Card 1num:
-// const LEFT_NUMS: &str = r": (\d+) (\d+) (\d+) (\d+) (\d+) |"; // : 5nums |
-// const RIGHT_NUMS: &str = r"| (\d+) (\d+) (\d+) (\d+) (\d+) (\d+) (\d+) (\d+)$"; // | 8 nums$
-
-// PERF:: for simple gobling up numbers in a row
-const NUM: &str = r"(\d+)";
+use crate::{
+        bitset::PackedPile,
+        custom_error::AocErrorDay04,
+        scratch_card::{CardPile, ScratchCard},
+};
 
 // #[tracing::instrument]
 pub fn process(input: &str) -> Result<u64, AocErrorDay04> {
         info!("Hiii. from  day-04 Part1! :)");
-        let pile: CardPile = input.lines().collect();
-
-        Ok(pile.into_iter()
-               .map(|card| card.wining_haves_overlap())
-               .filter(|&n| n > 0)
-               .map(|n| 2u64.pow(n as u32 - 1))
-               .sum())
-}
-
-/// Represents a single scratch card.
-
-#[derive(Constructor, Debug, PartialEq, Eq)]
-struct ScratchCard {
-        id:        u64,
-        // wins_arr: [u64; 5],
-        // haves_arr: [u64; 8],
-        wins_arr:  [u64; 10],
-        haves_arr: [u64; 25],
-}
-
-impl ScratchCard {
-        /// Gives the number of winning nums
-        /// assumes wins_arr could be a set (i.e. ignoring non-uniqueness) while haves_arr may have
-        /// duplicates
-        fn wining_haves_overlap(&self) -> u64 {
-                self.haves_arr
-                    .iter()
-                    .filter(|&n| self.wins_arr.contains(n))
-                    .count() as u64
-        }
-
-        /// NOTE: the multiple refernces to splits is error prone
-        fn from_str(line: &str) -> Option<Self> {
-                // static RE_CARD: Lazy<Regex> = Lazy::new(|| Regex::new(CARD_NUM).unwrap());
-                // static RE_LEFT: Lazy<Regex> = Lazy::new(|| Regex::new(LEFT_NUMS).unwrap());
-                // static RE_RIGHT: Lazy<Regex> = Lazy::new(|| Regex::new(RIGHT_NUMS).unwrap());
-                static RE_NUM: Lazy<Regex> = Lazy::new(|| Regex::new(NUM).unwrap());
-
-                let ordered_nums: Vec<u64> = RE_NUM.find_iter(line)
-                                                   .map(|m| {
-                                                           m.as_str()
-                                                            .parse::<u64>()
-                                                            .expect("parse failure")
-                                                   })
-                                                   .collect();
+        let pile: CardPile = input.parse()?;
 
-                match ordered_nums.len() {
-                        // 14 => Some(ScratchCard::new(
-                        36 => Some(ScratchCard::new(
-                                ordered_nums[0],
-                                // ordered_nums[1..6]
-                                ordered_nums[1..11]
-                                        .try_into()
-                                        .expect("vec to array failure: A"),
-                                // ordered_nums[6..14]
-                                ordered_nums[11..36]
-                                        .try_into()
-                                        .expect("vec to array failure: B"),
-                        )),
-                        _ => None,
-                }
-        }
+        score(pile.cards()
+                  .iter()
+                  .map(ScratchCard::matches))
 }
 
-/// Represents a pile of (scratch) cards.
-/// (simple wrapper)
-#[derive(Debug, PartialEq, Eq, IntoIterator)]
-struct CardPile(Vec<ScratchCard>);
-
-impl CardPile {
-        fn new() -> Self {
-                Self(Vec::new())
-        }
-
-        /// Gives a mutable reference to the pile's cards
-        fn cards(&mut self) -> &mut Vec<ScratchCard> {
-                &mut self.0
-        }
+/// Bitset version of `process`: matches counted by popcount across a packed pile.
+pub fn process_bitset(input: &str) -> Result<u64, AocErrorDay04> {
+        let pile: CardPile = input.parse()?;
+        score(PackedPile::try_from(&pile)?.matches())
 }
 
-impl<'a> FromIterator<&'a str> for CardPile {
-        fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
-                let mut pile = CardPile::new();
-
-                for item in iter {
-                        match ScratchCard::from_str(item) {
-                                Some(card) => pile.cards().push(card),
-                                None => panic!("Invalid card data on line {}", item),
-                        }
-                }
-
-                pile
-        }
+/// Points for a pile, from each card's matches: 1 for the first, doubled for each after.
+fn score(matches: impl IntoIterator<Item = u64>) -> Result<u64, AocErrorDay04> {
+        matches.into_iter()
+               .filter(|&n| n > 0)
+               .try_fold(0u64, |total, n| {
+                       u32::try_from(n - 1).ok()
+                                           .and_then(|shift| 1u64.checked_shl(shift))
+                                           .and_then(|points| total.checked_add(points))
+                                           .ok_or(AocErrorDay04::ScoreOverflow)
+               })
 }
 
 #[cfg(test)]
@@ -125,13 +44,9 @@ mod tests {
 
         use super::*;
 
-        // WARNING: the structure of the data is different from the main input
-        // the code is currently designed for the main input for perf reasons
-        // (and I just don't feel like doing multi pass or splitting)
-        #[ignore]
         #[test]
         fn test_process_example() -> Result<()> {
-                tracing_subscriber::fmt::init();
+                let _ = tracing_subscriber::fmt::try_init();
 
                 let input = indoc! {"
                         Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
@@ -146,6 +61,22 @@ mod tests {
                 Ok(())
         }
 
+        #[test]
+        fn test_score_overflow() -> Result<()> {
+                // one card matching every number in 1..=n
+                let card = |n: u64| {
+                        let numbers = (1..=n).map(|i| i.to_string())
+                                             .collect::<Vec<_>>()
+                                             .join(" ");
+                        format!("Card 1: {} | {}\n", numbers, numbers)
+                };
+                assert_eq!(process(&card(64))?, 1 << 63);
+                assert_eq!(process_bitset(&card(64))?, 1 << 63);
+                assert!(matches!(process(&card(65)), Err(AocErrorDay04::ScoreOverflow)));
+                assert!(matches!(process_bitset(&card(65)), Err(AocErrorDay04::ScoreOverflow)));
+                Ok(())
+        }
+
         /// This test's expected value is to be populated after
         /// verification of solution.
         /// (useful for future refactors and perfs)
@@ -155,6 +86,7 @@ mod tests {
                 let file_input = include_str!("../input1.txt");
                 let expected = 25183;
                 assert_eq!(process(file_input)?, expected);
+                assert_eq!(process_bitset(file_input)?, expected);
                 Ok(())
         }
 }
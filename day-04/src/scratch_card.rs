@@ -0,0 +1,239 @@
+//! Scratch cards and piles of them, shared by both parts.
+//!
+//! Cards are read by structure (`Card N:` / winning numbers / `|` / numbers you have),
+//! so list widths come from the input rather than being baked in.
+//! (The example has 5 | 8, the real input 10 | 25.)
+//! Every card in a pile must have the same widths as the first one,
+//! and ids must run in order with no gaps.
+
+use std::{fmt, str::FromStr};
+
+use aoc_common::{patterns, patterns::PatternError, regex::Captures};
+use tracing::debug;
+
+use crate::custom_error::{AocErrorDay04, CardProblem, InvalidCard};
+
+patterns! {
+        RE_CARD = r"^Card\s+(?<id>\d+):(?<winning>[\d ]*)\|(?<haves>[\d ]*)$";
+        RE_NUM = r"\d+";
+}
+
+/// How many numbers sit on each side of the `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Widths {
+        pub winning: usize,
+        pub haves:   usize,
+}
+
+impl fmt::Display for Widths {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{} | {}", self.winning, self.haves)
+        }
+}
+
+/// Represents a single scratch card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScratchCard {
+        pub id:      u64,
+        pub winning: Vec<u64>,
+        pub haves:   Vec<u64>,
+}
+
+impl ScratchCard {
+        pub fn widths(&self) -> Widths {
+                Widths { winning: self.winning.len(),
+                         haves:   self.haves.len(), }
+        }
+
+        /// Gives the number of winning nums
+        /// assumes `winning` could be a set (i.e. ignoring non-uniqueness) while `haves` may have
+        /// duplicates
+        pub fn matches(&self) -> u64 {
+                self.haves
+                    .iter()
+                    .filter(|&n| self.winning.contains(n))
+                    .count() as u64
+        }
+}
+
+impl FromStr for ScratchCard {
+        type Err = AocErrorDay04;
+
+        /// Parse errors are spanned within the (trimmed) line.
+        fn from_str(line: &str) -> Result<Self, Self::Err> {
+                let line = line.trim_end();
+                let caps = RE_CARD.captures_of(line)?;
+                Ok(Self { id:      RE_CARD.parse_group(&caps, "id")?,
+                          winning: parse_side(&caps, "winning", line)?,
+                          haves:   parse_side(&caps, "haves", line)?, })
+        }
+}
+
+/// Numbers of one side of a card.
+fn parse_side(caps: &Captures, group: &str, line: &str) -> Result<Vec<u64>, PatternError> {
+        let side = caps.name(group)
+                       .ok_or_else(|| PatternError::MissingGroup { name:  RE_CARD.name(),
+                                                                   group: group.to_string(), })?;
+        // `parse_all` spans the side alone; move that to the whole line
+        RE_NUM.parse_all(side.as_str())
+              .map_err(|e| match e {
+                      PatternError::Parse(mut e) => {
+                              e.span = (side.start() + e.span.offset(), e.span.len()).into();
+                              e.hay = line.to_string();
+                              PatternError::Parse(e)
+                      },
+                      other => other,
+              })
+}
+
+/// Represents a pile of (scratch) cards, all of one width.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardPile {
+        cards:  Vec<ScratchCard>,
+        widths: Widths,
+}
+
+impl CardPile {
+        pub fn cards(&self) -> &[ScratchCard] {
+                &self.cards
+        }
+
+        /// Widths shared by every card.
+        pub fn widths(&self) -> Widths {
+                self.widths
+        }
+}
+
+impl FromStr for CardPile {
+        type Err = AocErrorDay04;
+
+        /// One card per non-blank line.
+        /// Widths are set by the first card, and ids must count up by one from it.
+        /// Bad lines come back as `InvalidCard`, labelled within the whole input.
+        fn from_str(input: &str) -> Result<Self, Self::Err> {
+                let mut cards: Vec<ScratchCard> = Vec::new();
+                let mut offset = 0;
+                for raw in input.split_inclusive('\n') {
+                        let at = LineAt { input,
+                                          start: offset };
+                        offset += raw.len();
+                        let line = raw.trim_end();
+                        if line.trim().is_empty() {
+                                continue;
+                        }
+
+                        let card: ScratchCard = match line.parse() {
+                                Ok(card) => card,
+                                Err(AocErrorDay04::Pattern(PatternError::Parse(e))) => {
+                                        let span = (e.span.offset(), e.span.len());
+                                        return Err(at.invalid(CardProblem::Unreadable(e.reason),
+                                                              span));
+                                },
+                                Err(_) => {
+                                        let problem =
+                                                CardProblem::Unreadable(CARD_SHAPE.to_string());
+                                        return Err(at.invalid(problem, (0, line.len())));
+                                },
+                        };
+                        if let Some(first) = cards.first() {
+                                if card.widths() != first.widths() {
+                                        let problem = CardProblem::Width { found:    card.widths(),
+                                                                           expected: first.widths(), };
+                                        return Err(at.invalid(problem, (0, line.len())));
+                                }
+                                let expected = first.id + cards.len() as u64;
+                                if card.id != expected {
+                                        let problem = CardProblem::Id { found: card.id,
+                                                                        expected };
+                                        return Err(at.invalid(problem, id_span(line)));
+                                }
+                        }
+                        cards.push(card);
+                }
+                let widths = cards.first()
+                                  .map(ScratchCard::widths)
+                                  .ok_or(AocErrorDay04::EmptyPile)?;
+                debug!(cards = cards.len(), %widths, "card pile parsed");
+                Ok(Self { cards, widths })
+        }
+}
+
+const CARD_SHAPE: &str = "expected `Card <id>: <numbers> | <numbers>`";
+
+/// Where a line sits in the input, for building errors.
+struct LineAt<'a> {
+        input: &'a str,
+        start: usize,
+}
+
+impl LineAt<'_> {
+        /// `span` is `(offset, len)` within the line.
+        fn invalid(&self, problem: CardProblem, span: (usize, usize)) -> AocErrorDay04 {
+                InvalidCard::new("card", self.input, problem, (self.start + span.0, span.1)).into()
+        }
+}
+
+/// `(offset, len)` of a card line's id.
+fn id_span(line: &str) -> (usize, usize) {
+        let start = line.find(|c: char| c.is_ascii_digit())
+                        .unwrap_or(0);
+        let len = line[start..].find(|c: char| !c.is_ascii_digit())
+                               .unwrap_or(line.len() - start);
+        (start, len)
+}
+
+#[cfg(test)]
+mod tests {
+        use indoc::indoc;
+
+        use super::*;
+
+        #[test]
+        fn test_widths_discovered() -> Result<(), AocErrorDay04> {
+                let pile: CardPile = indoc! {"
+                        Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+                        Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+                "}.parse()?;
+                assert_eq!(pile.widths(), Widths { winning: 5,
+                                                   haves:   8, });
+                assert_eq!(pile.cards()[0].matches(), 4);
+
+                let real: CardPile = include_str!("../input1.txt").parse()?;
+                assert_eq!(real.widths(), Widths { winning: 10,
+                                                   haves:   25, });
+                Ok(())
+        }
+
+        /// The problem and labelled text of an `InvalidCard`.
+        fn invalid(input: &str) -> (usize, CardProblem, String) {
+                match input.parse::<CardPile>() {
+                        Err(AocErrorDay04::InvalidCard(e)) => {
+                                let span = e.span.offset()..e.span.offset() + e.span.len();
+                                (e.line, e.problem, input[span].to_string())
+                        },
+                        other => panic!("expected an invalid card, got {:?}", other),
+                }
+        }
+
+        #[test]
+        fn test_rejects_bad_piles() {
+                let (line, problem, _) = invalid("Card 1: 1 2 | 3 4 5\nCard 2: 1 2 3 | 4 5\n");
+                assert_eq!(line, 2);
+                assert!(matches!(problem, CardProblem::Width { .. }));
+
+                let (line, problem, text) = invalid("Card 1: 1 | 2\n\nCard 3: 1 | 2\n");
+                assert_eq!((line, text.as_str()), (3, "3"));
+                assert_eq!(problem, CardProblem::Id { found:    3,
+                                                      expected: 2, });
+
+                let (_, problem, text) =
+                        invalid("Card 1: 1 | 2\nCard 2: 1 | 99999999999999999999\n");
+                assert!(matches!(problem, CardProblem::Unreadable(_)));
+                assert_eq!(text, "99999999999999999999");
+
+                let (line, _, text) = invalid("Card 1: 1 2 3 4");
+                assert_eq!((line, text.as_str()), (1, "Card 1: 1 2 3 4"));
+
+                assert!(matches!("".parse::<CardPile>(), Err(AocErrorDay04::EmptyPile)));
+        }
+}
@@ -18,3 +18,13 @@ fn part1() {
 fn part2() {
         part2_lib::process(divan::black_box(include_str!("../input2.txt",))).unwrap();
 }
+
+#[divan::bench]
+fn part2_composed() {
+        part2_lib::process_composed(divan::black_box(include_str!("../input2.txt",))).unwrap();
+}
+
+#[divan::bench]
+fn part2_inverse() {
+        part2_lib::process_inverse(divan::black_box(include_str!("../input2.txt",))).unwrap();
+}
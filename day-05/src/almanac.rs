@@ -0,0 +1,716 @@
+//! Almanac maps, shared by both parts and the tools built on them.
+//!
+//! A `Map` is one `<input>-to-<output> map:` chunk: a list of `RangeBump`s.
+//! A value in a bump's range moves by its offset; anything else passes through unchanged.
+//! If bumps overlap, the first one listed wins.
+//!
+//! Maps also run backwards: `preimage` gives every value landing in a set of targets.
+//! (Maps needn't be one-to-one, so that's a set of ranges rather than an inverse map.)
+//!
+//! ## Routing
+//! Map headers name categories, so an almanac is a graph with an edge per map.
+//! It must be a single path from `seed`: each category maps to at most one other,
+//! no category leads back to itself, and every map is on the path.
+//! Maps may come in any order; `Almanac` keeps them in path order.
+//!
+//! ## Seeds
+//! Part 1 reads each number on the seeds line as a seed, part 2 reads `<start> <length>` pairs
+//! (`SeedsLine`). Either way the seeds are a `RangeSet`: part 1's are one-value ranges.
+//!
+//! ## Checks
+//! Each line must give a positive length, and the ends of both its ranges must fit in an `i64`;
+//! otherwise it's an `InvalidMapLine`. (So must the end of each seed range.)
+//! Overlapping source ranges within a map are allowed (first wins), but come back as
+//! `OverlapWarning`s from `Almanac::parse_with_warnings` (and are logged by `parse`).
+
+use std::{collections::HashMap, fmt, ops::Range, str::FromStr};
+
+use aoc_common::{
+        captures::FromCaptures, patterns, patterns::PatternError, regex::Captures, spanned::Excerpt,
+};
+use derive_more::Constructor;
+use tracing::{debug, trace, warn};
+
+use crate::{
+        custom_error::{AocErrorDay05, InvalidMapLine, MapProblem, OverlapWarning},
+        piecewise::Piecewise,
+        range_math::{RangeMath, RangeSet},
+};
+
+// Capture Patterns for Regex generation
+patterns! { RE_SEEDS = r"seeds: (?<seednumbers>.*)$"; }
+patterns! { RE_NUM = r"\d+"; }
+patterns! { RE_NUM_PAIR = r"(?<base>\d+) (?<rangeoffset>\d+)"; }
+patterns! { RE_A_TO_B = r"(?<input>\w+)-to-(?<output>\w+) map:"; }
+patterns! { RE_VAL_MAP = r"^(?<outstart>\d+) (?<instart>\d+) (?<length>\d+)"; }
+
+/// input & output values and 'range-bumps' that may modify an object
+/// PERF: replace Strings with &str referencing static strings.
+#[derive(Debug, PartialEq, Eq, Constructor)]
+pub struct Map {
+        pub inp:   String,
+        pub out:   String,
+        pub rmaps: Vec<RangeBump>,
+}
+
+impl Map {
+        /// Passes a value in and passes it back, incremented if it matches any of the ranges.
+        /// With**out** validating input kind or relaying output kind.
+        pub fn val_only_passthrough(&self, val: i64) -> i64 {
+                self.rmaps
+                    .iter()
+                    .filter_map(|rmap| rmap.try_bump(val))
+                    .next()
+                    .unwrap_or(val)
+        }
+
+        /// The bump that moves `val`, if any.
+        pub fn bump_for(&self, val: i64) -> Option<&RangeBump> {
+                self.rmaps
+                    .iter()
+                    .find(|rmap| rmap.range.contains(&val))
+        }
+
+        /// `(earlier, later)` indices of bumps whose source ranges overlap.
+        pub fn overlaps(&self) -> Vec<(usize, usize)> {
+                let mut pairs = Vec::new();
+                for (j, later) in self.rmaps.iter().enumerate() {
+                        for (i, earlier) in self.rmaps[..j].iter()
+                                                           .enumerate()
+                        {
+                                if earlier.range
+                                          .overlaps(&later.range)
+                                {
+                                        pairs.push((i, j));
+                                }
+                        }
+                }
+                pairs
+        }
+
+        /// Every value this map sends into `targets`.
+        pub fn preimage(&self, targets: &RangeSet<i64>) -> RangeSet<i64> {
+                Piecewise::from_map(self).preimage(targets)
+        }
+
+        /// `val_only_passthrough` for every value of a set of ranges at once.
+        /// As there, the first bump covering a value is the one applied.
+        pub fn range_passthrough(&self, vals: &RangeSet<i64>) -> RangeSet<i64> {
+                let mut unbumped = vals.clone();
+                let mut out = RangeSet::new();
+                for rmap in &self.rmaps {
+                        let bump = RangeSet::from(rmap.range.clone());
+                        for hit in unbumped.intersection(&bump)
+                                           .ranges()
+                        {
+                                out.insert(hit.shift(rmap.offset));
+                        }
+                        unbumped = unbumped.difference(&bump);
+                }
+                out.union(&unbumped)
+        }
+}
+
+impl FromStr for Map {
+        type Err = AocErrorDay05;
+
+        // Populate a map from a contiguous chunk of map string data.
+        fn from_str(chunk: &str) -> Result<Self, Self::Err> {
+                let mut lines = chunk.lines();
+                // an empty chunk fails below, as a header that doesn't match
+                let first_line = lines.next()
+                                      .unwrap_or_default();
+                trace!("first_line: {:?}", first_line);
+
+                let MapHeader { input, output } = first_line.parse()?;
+                let rmaps = lines.enumerate()
+                                 .map(|(i, line)| {
+                                         let ValMap { outstart,
+                                                      instart,
+                                                      length, } = line.parse()?;
+                                         RangeBump::from_line(outstart, instart, length)
+                                                 .map_err(|problem| invalid_line(chunk, i + 1, problem))
+                                 })
+                                 .collect::<Result<Vec<_>, AocErrorDay05>>()?;
+
+                Ok(Self::new(input, output, rmaps))
+        }
+}
+
+/// Category the seeds line is in, where every route through an almanac starts.
+pub const SEED: &str = "seed";
+
+/// How to read the seeds line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SeedsLine {
+        /// Each number is a seed. (Part 1.)
+        Values,
+        /// `<start> <length>` pairs. (Part 2.)
+        #[default]
+        Ranges,
+}
+
+/// Seed ranges, and the maps to run them through.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Almanac {
+        pub seeds: RangeSet<i64>,
+        /// in route order: `seed` to the last category
+        pub maps:  Vec<Map>,
+}
+
+/// One map applied to one value, as recorded by `Almanac::trace`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step<'a> {
+        pub map:  &'a Map,
+        pub from: i64,
+        pub to:   i64,
+        /// `None` when the value passed through unchanged
+        pub bump: Option<&'a RangeBump>,
+}
+
+impl Almanac {
+        /// Every category, in route order.
+        pub fn categories(&self) -> impl Iterator<Item = &str> {
+                std::iter::once(SEED).chain(self.maps
+                                                .iter()
+                                                .map(|map| map.out.as_str()))
+        }
+
+        /// The maps taking `from` values to `to` values, in order.
+        /// (Empty if `from` is `to`.)
+        pub fn route(&self, from: &str, to: &str) -> Result<&[Map], AocErrorDay05> {
+                let at = |category: &str| {
+                        self.categories()
+                            .position(|c| c == category)
+                };
+                match (at(from), at(to)) {
+                        (Some(i), Some(j)) if i <= j => Ok(&self.maps[i..j]),
+                        _ => Err(AocErrorDay05::NoRoute { from: from.to_string(),
+                                                          to:   to.to_string(), }),
+                }
+        }
+
+        /// A `from` value as a `to` value.
+        pub fn convert(&self, from: &str, to: &str, val: i64) -> Result<i64, AocErrorDay05> {
+                Ok(self.route(from, to)?
+                       .iter()
+                       .fold(val, |acc, map| map.val_only_passthrough(acc)))
+        }
+
+        /// All the maps as one function.
+        pub fn function(&self) -> Piecewise {
+                Piecewise::chain(&self.maps)
+        }
+
+        /// Where every seed ends up.
+        pub fn locations(&self) -> RangeSet<i64> {
+                self.maps
+                    .iter()
+                    .fold(self.seeds.clone(), |acc, map| {
+                            let temp = map.range_passthrough(&acc);
+                            debug!(map = %map.out, ranges = temp.ranges().len());
+                            temp
+                    })
+        }
+
+        /// Seeds (of the almanac's own) that end up in `locations`.
+        pub fn seeds_reaching(&self, locations: Range<i64>) -> RangeSet<i64> {
+                self.maps
+                    .iter()
+                    .rev()
+                    .fold(RangeSet::from(locations), |acc, map| map.preimage(&acc))
+                    .intersection(&self.seeds)
+        }
+
+        /// The path of one seed through every map.
+        pub fn trace(&self, seed: i64) -> Vec<Step<'_>> {
+                let mut val = seed;
+                self.maps
+                    .iter()
+                    .map(|map| {
+                            let bump = map.bump_for(val);
+                            let from = val;
+                            val = bump.map_or(val, |b| val + b.offset);
+                            Step { map,
+                                   from,
+                                   to: val,
+                                   bump }
+                    })
+                    .collect()
+        }
+}
+
+impl Almanac {
+        /// Parse, reading the seeds line as asked. Overlapping ranges are logged as warnings.
+        pub fn parse_seeds_as(input: &str, seeds: SeedsLine) -> Result<Self, AocErrorDay05> {
+                let (almanac, warnings) = Self::parse_with_warnings(input, seeds)?;
+                for w in warnings {
+                        warn!("{}", w);
+                }
+                Ok(almanac)
+        }
+
+        /// Parse, also returning a warning per pair of overlapping lines.
+        /// Errors and warnings are labelled within the whole input.
+        pub fn parse_with_warnings(input: &str,
+                                   seeds: SeedsLine)
+                                   -> Result<(Self, Vec<OverlapWarning>), AocErrorDay05> {
+                // splitting input along blank lines (blank lines discarded)
+                let mut it_chunk = input.split("\n\n");
+                let first_chunk = it_chunk.next()
+                                          .unwrap_or_default();
+
+                let seeds = read_seeds(first_chunk, seeds)?;
+                debug!("seeds: {:?}", seeds);
+
+                let mut maps = Vec::new();
+                let mut warnings = Vec::new();
+                // where each chunk starts: byte offset, and 0-based line
+                let mut start = first_chunk.len() + 2;
+                let mut first_line = first_chunk.matches('\n')
+                                                .count()
+                                     + 2;
+                for chunk in it_chunk {
+                        let at = |(offset, len): (usize, usize)| (start + offset, len);
+                        let map: Map = chunk.parse()
+                                            .map_err(|e| match e {
+                                                    AocErrorDay05::InvalidMapLine(e) =>
+                                                            (*e).within(input, start)
+                                                                .into(),
+                                                    other => other,
+                                            })?;
+                        // bump `i` is on line `i + 1` of its chunk
+                        for (earlier, later) in map.overlaps() {
+                                let span = at(line_span(chunk, later + 1)).into();
+                                let first = at(line_span(chunk, earlier + 1)).into();
+                                let (line, earlier) =
+                                        (first_line + later + 2, first_line + earlier + 2);
+                                warnings.push(OverlapWarning { map: format!("{}-to-{}",
+                                                                            map.inp, map.out),
+                                                               line,
+                                                               earlier,
+                                                               src: Excerpt::new(input,
+                                                                                 &[span,
+                                                                                   first]),
+                                                               span,
+                                                               first });
+                        }
+                        maps.push(map);
+                        start += chunk.len() + 2;
+                        first_line += chunk.matches('\n').count() + 2;
+                }
+                Ok((Self { seeds,
+                           maps: route_order(maps)? },
+                    warnings))
+        }
+}
+
+impl FromStr for Almanac {
+        type Err = AocErrorDay05;
+
+        /// Seeds line (as seed ranges), then map chunks, separated by blank lines.
+        /// Overlapping ranges are logged as warnings.
+        fn from_str(input: &str) -> Result<Self, Self::Err> {
+                Self::parse_seeds_as(input, SeedsLine::Ranges)
+        }
+}
+
+/// `(offset, len)` of line `index` (0-based) of `src`.
+fn line_span(src: &str, index: usize) -> (usize, usize) {
+        let mut offset = 0;
+        for (i, line) in src.split_inclusive('\n')
+                            .enumerate()
+        {
+                if i == index {
+                        return (offset, line.trim_end().len());
+                }
+                offset += line.len();
+        }
+        (offset, 0)
+}
+
+/// `InvalidMapLine` for line `index` (0-based) of `src`.
+fn invalid_line(src: &str, index: usize, problem: MapProblem) -> AocErrorDay05 {
+        InvalidMapLine::new("almanac line", src, problem, line_span(src, index)).into()
+}
+
+/// The maps along the path from `seed`, rejecting anything that isn't one clean path.
+fn route_order(maps: Vec<Map>) -> Result<Vec<Map>, AocErrorDay05> {
+        let mut by_inp: HashMap<&str, usize> = HashMap::new();
+        for (i, map) in maps.iter().enumerate() {
+                if let Some(&j) = by_inp.get(map.inp.as_str()) {
+                        return Err(AocErrorDay05::AmbiguousRoute { category: map.inp.clone(),
+                                                                   first:    maps[j].out.clone(),
+                                                                   second:   map.out.clone(), });
+                }
+                by_inp.insert(&map.inp, i);
+        }
+        // one map out of each category, so following maps from one can only loop or stop
+        for map in &maps {
+                let mut category = map.out.as_str();
+                for _ in 0..maps.len() {
+                        if category == map.inp {
+                                let category = category.to_string();
+                                return Err(AocErrorDay05::RouteCycle { category });
+                        }
+                        match by_inp.get(category) {
+                                Some(&next) => category = &maps[next].out,
+                                None => break,
+                        }
+                }
+        }
+
+        let mut order: Vec<usize> = Vec::with_capacity(maps.len());
+        let mut category = SEED;
+        while let Some(&i) = by_inp.get(category) {
+                order.push(i);
+                category = &maps[i].out;
+        }
+        if let Some((_, unreached)) = maps.iter()
+                                          .enumerate()
+                                          .find(|(i, _)| !order.contains(i))
+        {
+                return Err(AocErrorDay05::RouteGap { from:      SEED.to_string(),
+                                                     unreached: unreached.inp.clone(), });
+        }
+        debug!(route = category, maps = order.len(), "maps routed");
+
+        let mut slots: Vec<Option<Map>> = maps.into_iter()
+                                              .map(Some)
+                                              .collect();
+        Ok(order.into_iter()
+                .filter_map(|i| slots[i].take())
+                .collect())
+}
+
+/// Read the seeds line (the first line of the input) into seed ranges.
+fn read_seeds(line: &str, seeds: SeedsLine) -> Result<RangeSet<i64>, AocErrorDay05> {
+        let Some(_) = RE_SEEDS.captures(line)
+        else {
+                return Err(AocErrorDay05::SeedsParse("Failed to parse seeds".to_string()));
+        };
+
+        // `(start, length, where it's written)` for each range
+        let ranges: Vec<(i64, i64, Range<usize>)> = match seeds {
+                SeedsLine::Values => RE_NUM.parse_all::<i64>(line)?
+                                           .into_iter()
+                                           .zip(RE_NUM.find_iter(line))
+                                           .map(|(val, m)| (val, 1, m.range()))
+                                           .collect(),
+                SeedsLine::Ranges => RE_NUM_PAIR.captures_iter(line)
+                                                .map(|caps| seed_range(&caps))
+                                                .collect::<Result<_, _>>()?,
+        };
+        ranges.into_iter()
+              .map(|(start, len, at)| {
+                      let end = start.checked_add(len)
+                                     .ok_or_else(|| {
+                                             let problem =
+                                                     MapProblem::Overflow("seed start + length");
+                                             InvalidMapLine::new("almanac line",
+                                                                 line,
+                                                                 problem,
+                                                                 (at.start, at.len()))
+                                     })?;
+                      Ok(start..end)
+              })
+              .collect()
+}
+
+/// A seeds line pair's numbers, and where it's written.
+fn seed_range(caps: &Captures) -> Result<(i64, i64, Range<usize>), PatternError> {
+        let SeedRange { base, rangeoffset } = SeedRange::from_captures(&RE_NUM_PAIR, caps)?;
+        let whole = caps.get(0)
+                        .expect("group 0 always participates");
+        Ok((base, rangeoffset, whole.range()))
+}
+
+/// `<base> <rangeoffset>` pair from the seeds line.
+#[derive(FromCaptures)]
+struct SeedRange {
+        base:        i64,
+        rangeoffset: i64,
+}
+
+/// `<input>-to-<output> map:` line opening each map chunk.
+#[derive(FromCaptures)]
+#[captures(pattern = RE_A_TO_B)]
+struct MapHeader {
+        input:  String,
+        output: String,
+}
+
+/// One `<outstart> <instart> <length>` line of a map chunk.
+#[derive(FromCaptures)]
+#[captures(pattern = RE_VAL_MAP)]
+struct ValMap {
+        outstart: i64,
+        instart:  i64,
+        length:   i64,
+}
+
+/// A range of values and the amount they are bumped by.
+#[derive(Debug, Clone, PartialEq, Eq, Constructor)]
+pub struct RangeBump {
+        pub offset: i64,
+        pub range:  Range<i64>,
+}
+
+impl RangeBump {
+        /// From the numbers of a `<outstart> <instart> <length>` line,
+        /// checking the length is positive and neither range runs past `i64::MAX`.
+        pub fn from_line(outstart: i64, instart: i64, length: i64) -> Result<Self, MapProblem> {
+                if length <= 0 {
+                        return Err(MapProblem::Length(length));
+                }
+                let end = instart.checked_add(length)
+                                 .ok_or(MapProblem::Overflow("instart + length"))?;
+                // the largest bumped value is `outstart + length - 1`
+                outstart.checked_add(length)
+                        .ok_or(MapProblem::Overflow("outstart + length"))?;
+                Ok(Self::new(outstart - instart, instart..end))
+        }
+
+        /// Returns true if the given value is in the range.
+        /// If so, the offset is applied to the value.
+        pub fn try_bump(&self, val: i64) -> Option<i64> {
+                debug!(?val, ?self);
+                if self.range.contains(&val) {
+                        Some(val + self.offset)
+                }
+                else {
+                        None
+                }
+        }
+}
+
+/// As an almanac line: `<outstart> <instart> <length>`.
+impl fmt::Display for RangeBump {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f,
+                       "{} {} {}",
+                       self.range.start + self.offset,
+                       self.range.start,
+                       self.range.end - self.range.start)
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use indoc::indoc;
+
+        use super::*;
+
+        const EXAMPLE: &str = indoc! {"
+                seeds: 79 14 55 13
+
+                seed-to-soil map:
+                50 98 2
+                52 50 48
+
+                soil-to-fertilizer map:
+                0 15 37
+                37 52 2
+                39 0 15
+
+                fertilizer-to-water map:
+                49 53 8
+                0 11 42
+                42 0 7
+                57 7 4
+
+                water-to-light map:
+                88 18 7
+                18 25 70
+
+                light-to-temperature map:
+                45 77 23
+                81 45 19
+                68 64 13
+
+                temperature-to-humidity map:
+                0 69 1
+                1 0 69
+
+                humidity-to-location map:
+                60 56 37
+                56 93 4
+        "};
+
+        #[test]
+        fn test_trace() -> Result<(), AocErrorDay05> {
+                let almanac: Almanac = EXAMPLE.parse()?;
+                let path: Vec<i64> = almanac.trace(79)
+                                            .iter()
+                                            .map(|step| step.to)
+                                            .collect();
+                assert_eq!(path, vec![81, 81, 81, 74, 78, 78, 82]);
+                let steps = almanac.trace(79);
+                assert_eq!(steps[0].bump
+                                   .map(|b| b.to_string()),
+                           Some("52 50 48".to_string()));
+                assert_eq!(steps[1].bump, None);
+                Ok(())
+        }
+
+        /// Example maps, reordered by `order` (indices into the example's map chunks).
+        fn reordered(order: &[usize]) -> String {
+                let mut chunks = EXAMPLE.trim_end()
+                                        .split("\n\n");
+                let seeds = chunks.next()
+                                  .unwrap_or_default();
+                let maps: Vec<&str> = chunks.collect();
+                std::iter::once(seeds).chain(order.iter().map(|&i| maps[i]))
+                                      .collect::<Vec<_>>()
+                                      .join("\n\n")
+        }
+
+        #[test]
+        fn test_routes_any_order() -> Result<(), AocErrorDay05> {
+                let almanac: Almanac = EXAMPLE.parse()?;
+                let shuffled: Almanac = reordered(&[3, 6, 0, 5, 2, 1, 4]).parse()?;
+                assert_eq!(shuffled, almanac);
+                assert_eq!(almanac.categories()
+                                  .collect::<Vec<_>>(),
+                           vec!["seed",
+                                "soil",
+                                "fertilizer",
+                                "water",
+                                "light",
+                                "temperature",
+                                "humidity",
+                                "location"]);
+
+                let route = almanac.route("soil", "humidity")?;
+                assert_eq!((route.len(), route[0].inp.as_str(), route[4].out.as_str()),
+                           (5, "soil", "humidity"));
+                assert!(almanac.route("water", "water")?
+                               .is_empty());
+                // seed 79 is soil 81, and humidity 78
+                assert_eq!(almanac.convert("soil", "humidity", 81)?, 78);
+                assert!(matches!(almanac.route("location", "seed"),
+                                 Err(AocErrorDay05::NoRoute { .. })));
+                assert!(matches!(almanac.route("seed", "moon"),
+                                 Err(AocErrorDay05::NoRoute { .. })));
+                Ok(())
+        }
+
+        #[test]
+        fn test_rejects_bad_routes() {
+                let gap = reordered(&[0, 2, 3, 4, 5, 6]);
+                assert!(matches!(gap.parse::<Almanac>(),
+                                 Err(AocErrorDay05::RouteGap { unreached, .. }) if unreached == "fertilizer"));
+
+                let twice = format!("{}\n\nseed-to-water map:\n1 2 3", EXAMPLE.trim_end());
+                assert!(matches!(twice.parse::<Almanac>(),
+                                 Err(AocErrorDay05::AmbiguousRoute { category, .. }) if category == "seed"));
+
+                let looped = format!("{}\n\nlocation-to-soil map:\n1 2 3", EXAMPLE.trim_end());
+                assert!(matches!(looped.parse::<Almanac>(),
+                                 Err(AocErrorDay05::RouteCycle { .. })));
+                // a loop off to the side of the path is still a loop
+                let aside = format!("{}\n\nmoon-to-sun map:\n1 2 3\n\nsun-to-moon map:\n1 2 3",
+                                    EXAMPLE.trim_end());
+                assert!(matches!(aside.parse::<Almanac>(),
+                                 Err(AocErrorDay05::RouteCycle { .. })));
+        }
+
+        /// The `InvalidMapLine` from parsing `input`: its line, problem and labelled text.
+        fn invalid(input: &str) -> (usize, MapProblem, String) {
+                match input.parse::<Almanac>() {
+                        Err(AocErrorDay05::InvalidMapLine(e)) => {
+                                let span = e.span.offset()..e.span.offset() + e.span.len();
+                                (e.line, e.problem, input[span].to_string())
+                        },
+                        other => panic!("expected an invalid map line, got {:?}", other),
+                }
+        }
+
+        #[test]
+        fn test_rejects_bad_lines() {
+                let (line, problem, text) =
+                        invalid("seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n5 6 0\n");
+                assert_eq!((line, problem, text.as_str()),
+                           (5, MapProblem::Length(0), "5 6 0"));
+
+                let (line, problem, _) =
+                        invalid("seeds: 1 2\n\nseed-to-soil map:\n1 9223372036854775800 10");
+                assert_eq!((line, problem),
+                           (4, MapProblem::Overflow("instart + length")));
+
+                let (_, problem, _) =
+                        invalid("seeds: 1 2\n\nseed-to-soil map:\n9223372036854775800 1 10");
+                assert_eq!(problem, MapProblem::Overflow("outstart + length"));
+
+                assert_eq!(RangeBump::from_line(0, 5, -1), Err(MapProblem::Length(-1)));
+
+                // seed ranges, labelled by pair
+                let (line, problem, text) =
+                        invalid("seeds: 1 2 9223372036854775800 10\n\nseed-to-soil map:\n1 2 3");
+                assert_eq!((line, problem, text.as_str()),
+                           (1,
+                            MapProblem::Overflow("seed start + length"),
+                            "9223372036854775800 10"));
+                // and part 1's one-value ranges
+                let max = "seeds: 5 9223372036854775807\n\nseed-to-soil map:\n1 2 3";
+                assert!(matches!(Almanac::parse_seeds_as(max, SeedsLine::Values),
+                                 Err(AocErrorDay05::InvalidMapLine(e)) if e.span.offset() == 9));
+        }
+
+        #[test]
+        fn test_overlap_warnings() -> Result<(), AocErrorDay05> {
+                let input = indoc! {"
+                        seeds: 1 2
+
+                        seed-to-soil map:
+                        50 10 5
+                        60 0 5
+                        70 12 10
+                "};
+                let (almanac, warnings) = Almanac::parse_with_warnings(input, SeedsLine::Ranges)?;
+                assert_eq!(almanac.maps[0].overlaps(), vec![(0, 2)]);
+                let [w] = warnings.as_slice()
+                else {
+                        panic!("expected one warning, got {:?}", warnings);
+                };
+                assert_eq!((w.map.as_str(), w.line, w.earlier), ("seed-to-soil", 6, 4));
+                assert_eq!(&input[w.span.offset()..w.span.offset() + w.span.len()],
+                           "70 12 10");
+                assert_eq!(&input[w.first.offset()..w.first.offset() + w.first.len()],
+                           "50 10 5");
+
+                // the puzzle's own maps don't overlap
+                let (_, warnings) = Almanac::parse_with_warnings(include_str!("../input2.txt"),
+                                                                 SeedsLine::Ranges)?;
+                assert!(warnings.is_empty());
+                Ok(())
+        }
+
+        #[test]
+        fn test_seeds_reaching() -> Result<(), AocErrorDay05> {
+                let almanac: Almanac = EXAMPLE.parse()?;
+                assert_eq!(almanac.seeds_reaching(46..47), RangeSet::from(82..83));
+                assert!(almanac.seeds_reaching(0..46)
+                               .is_empty());
+
+                // stepping back map by map agrees with the composed function
+                let targets = RangeSet::from_iter([40..60, 80..90]);
+                let stepped = almanac.maps
+                                     .iter()
+                                     .rev()
+                                     .fold(targets.clone(), |acc, map| map.preimage(&acc));
+                assert_eq!(stepped,
+                           almanac.function()
+                                  .preimage(&targets));
+                for seed in 0..120 {
+                        let lands = almanac.trace(seed)
+                                           .last()
+                                           .map_or(seed, |step| step.to);
+                        assert_eq!(stepped.contains(&seed),
+                                   targets.contains(&lands),
+                                   "seed {}",
+                                   seed);
+                }
+                Ok(())
+        }
+}
@@ -0,0 +1,81 @@
+//! Lookup CLI for Day 05 of Advent of Code 2023.
+//! Queries the almanac in `input2.txt` (or `--input <path>`), forwards or backwards.
+//!
+//! ## Usage
+//! ```text
+//! lookup [--input <path>] seed <n>             every map seed n passes through, and how
+//! lookup [--input <path>] locations <a> <b>    the almanac's seeds that land in a..b
+//! ```
+
+use day_05::almanac::Almanac;
+use miette::{miette, Context, IntoDiagnostic, Result};
+
+const USAGE: &str = "usage: lookup [--input <path>] (seed <n> | locations <a> <b>)";
+
+/// Value following `flag`, removed from `args` along with the flag.
+fn take_flag(args: &mut Vec<String>, flag: &str) -> Result<Option<String>> {
+        let Some(i) = args.iter()
+                          .position(|a| a == flag)
+        else {
+                return Ok(None);
+        };
+        let value = args.get(i + 1)
+                        .ok_or_else(|| miette!("{} needs a value\n{}", flag, USAGE))?
+                        .clone();
+        args.drain(i..=i + 1);
+        Ok(Some(value))
+}
+
+fn number(arg: &str) -> Result<i64> {
+        arg.parse()
+           .into_diagnostic()
+           .wrap_err_with(|| format!("not a number: {:?}", arg))
+}
+
+fn main() -> Result<()> {
+        tracing_subscriber::fmt::init();
+
+        let mut args: Vec<String> = std::env::args().skip(1)
+                                                    .collect();
+        let input = match take_flag(&mut args, "--input")? {
+                Some(path) =>
+                        std::fs::read_to_string(&path).into_diagnostic()
+                                                      .wrap_err_with(|| format!("read {}", path))?,
+                None => include_str!("../../input2.txt").to_string(),
+        };
+        let almanac: Almanac = input.parse()
+                                    .context("parse almanac")?;
+
+        match args.iter()
+                  .map(String::as_str)
+                  .collect::<Vec<_>>()
+                  .as_slice()
+        {
+                ["seed", n] => {
+                        let seed = number(n)?;
+                        if !almanac.seeds.contains(&seed) {
+                                println!("(seed {} is not among the almanac's seeds)", seed);
+                        }
+                        for step in almanac.trace(seed) {
+                                let by =
+                                        step.bump
+                                            .map_or("unchanged".to_string(), |b| {
+                                                    format!("by `{}`", b)
+                                            });
+                                println!("{:>12} {:<12} -> {:>12} {:<12} {}",
+                                         step.map.inp, step.from, step.map.out, step.to, by);
+                        }
+                },
+                ["locations", a, b] => {
+                        let reaching = almanac.seeds_reaching(number(a)?..number(b)?);
+                        if reaching.is_empty() {
+                                println!("no seeds land in {}..{}", a, b);
+                        }
+                        for r in reaching.ranges() {
+                                println!("seeds {}..{}", r.start, r.end);
+                        }
+                },
+                _ => return Err(miette!("{}", USAGE)),
+        }
+        Ok(())
+}
@@ -1,6 +1,12 @@
 //! Custom Error type for Day 05 of Advent of Code 2023.
 
-use miette::Diagnostic;
+use std::fmt;
+
+use aoc_common::{
+        patterns::PatternError,
+        spanned::{Excerpt, InvalidInput},
+};
+use miette::{Diagnostic, SourceSpan};
 use thiserror::Error;
 
 #[derive(Error, Diagnostic, Debug)]
@@ -16,4 +22,84 @@ pub enum AocErrorDay05 {
         #[error("Error parsing seeds: {0}")]
         #[diagnostic(code(aoc::min_unwrap_error))]
         MinFailure(String),
+
+        #[error(transparent)]
+        #[diagnostic(transparent)]
+        Pattern(#[from] PatternError),
+
+        #[error(transparent)]
+        #[diagnostic(transparent)]
+        InvalidMapLine(Box<InvalidMapLine>),
+
+        #[error("Ambiguous route: `{category}` maps to both `{first}` and `{second}`")]
+        #[diagnostic(code(aoc::ambiguous_route))]
+        AmbiguousRoute {
+                category: String,
+                first:    String,
+                second:   String,
+        },
+
+        #[error("Cycle in maps: `{category}` leads back round to itself")]
+        #[diagnostic(code(aoc::route_cycle))]
+        RouteCycle { category: String },
+
+        #[error("Gap in maps: the `{unreached}` map can't be reached from `{from}`")]
+        #[diagnostic(code(aoc::route_gap))]
+        RouteGap {
+                from:      String,
+                unreached: String,
+        },
+
+        #[error("No route from `{from}` to `{to}`")]
+        #[diagnostic(code(aoc::no_route))]
+        NoRoute { from: String, to: String },
+}
+
+/// A map line (or the seeds line) whose numbers don't make a usable range.
+/// Labelled within the map chunk or, from `Almanac`, the whole input.
+pub type InvalidMapLine = InvalidInput<MapProblem>;
+
+impl From<InvalidMapLine> for AocErrorDay05 {
+        fn from(err: InvalidMapLine) -> Self {
+                Self::InvalidMapLine(Box::new(err))
+        }
+}
+
+/// What's wrong with an `InvalidMapLine`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapProblem {
+        /// Ranges need at least one value.
+        Length(i64),
+        /// The sum named doesn't fit in an `i64`.
+        Overflow(&'static str),
+}
+
+impl fmt::Display for MapProblem {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                        Self::Length(n) => write!(f, "length must be positive, found {}", n),
+                        Self::Overflow(sum) => write!(f, "`{}` overflows i64", sum),
+                }
+        }
+}
+
+/// Two lines of one map whose source ranges overlap.
+/// Legal, but only the first line applies to the shared values, which is rarely what was meant.
+#[derive(Error, Diagnostic, Debug)]
+#[error("`{map}` map: line {line} overlaps line {earlier}")]
+#[diagnostic(code(aoc::overlapping_ranges),
+             severity(Warning),
+             help("values in both ranges take the earlier line's offset"))]
+pub struct OverlapWarning {
+        pub map:     String,
+        /// 1-based
+        pub line:    usize,
+        /// 1-based
+        pub earlier: usize,
+        #[source_code]
+        pub src:     Excerpt,
+        #[label("ignored where it overlaps")]
+        pub span:    SourceSpan,
+        #[label("applies")]
+        pub first:   SourceSpan,
 }
@@ -1,27 +1,18 @@
 //! Library code for Part 2 of Day 05 of Advent of Code 2023.
 //! `bin > part2.rs` will run this code along with conent of `input2.txt`
 
-use std::ops::Range;
-
-use derive_more::Constructor;
 use miette::Result;
-use once_cell::sync::Lazy;
-use regex::Regex;
-use tracing::{debug, info, trace};
-
-use crate::custom_error::AocErrorDay05;
+use tracing::{debug, info};
 
-// Capture Patterns for Regex generation
-static RE_SEEDS: Lazy<Regex> = Lazy::new(|| Regex::new(r"seeds: (?<seednumbers>.*)$").unwrap());
-static RE_NUM_PAIR: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"(?<base>\d+) (?<rangeoffset>\d+)").unwrap());
-static RE_A_TO_B: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"(?<input>\w+)-to-(?<output>\w+) map:").unwrap());
-static RE_VAL_MAP: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"^(?<outstart>\d+) (?<instart>\d+) (?<length>\d+)").unwrap());
+use crate::{almanac::Almanac, custom_error::AocErrorDay05, range_math::RangeSet};
 
 /// Return lowest "seed" to "location" mapping's location value.
-/// Parse seeds, maps kinds, and value map ranges.
+/// Parse seed ranges, maps kinds, and value map ranges.
+///
+/// Seeds come as `<start> <length>` pairs, which cover billions of values in the real input.
+/// So rather than moving each seed through the maps, whole ranges are moved:
+/// each map cuts a range at its `RangeBump` boundaries and shifts the pieces.
+/// The answer is the lowest start of the ranges that come out the other end.
 ///
 /// ## NOTES:
 /// - Values are approaching u32 MAX at least.
@@ -30,6 +21,7 @@ static RE_VAL_MAP: Lazy<Regex> =
 ///   - sample_input_val ==      2_906_633_798
 /// - Part1 mappings appear to be ordered.
 ///   - e.g. a to b, b to c, c to d
+///   - **EDIT**: no longer relied on; `Almanac` routes maps by their headers.
 ///   - We could use a vector and skip mapping kind parsing or lookup.
 ///   - alt: we could HashMap for generality
 ///     - alt, alt: we could BTree to 'take the difference'
@@ -52,7 +44,7 @@ static RE_VAL_MAP: Lazy<Regex> =
 ///   - apply offset if so
 ///   - continue
 /// - Return lowest when done
-
+///
 /// ## Design Thoughts:
 /// A natural desire for this implementation would be an 'enum' of the map kinds.
 /// However, those are dynamically determined.  A lack of subtyping would also make a struct
@@ -64,122 +56,67 @@ static RE_VAL_MAP: Lazy<Regex> =
 /// to from tags)
 // #[tracing::instrument]
 pub fn process(input: &str) -> Result<i64, AocErrorDay05> {
-        info!("Hiii. from  day-05 Part1! :)");
-        // splitting input along blank lines (blank lines discarded)
-        let mut it_chunk = input.split("\n\n");
-        let first_line = it_chunk.next()
-                                 .expect("empty input");
+        info!("Hiii. from  day-05 Part2! :)");
+        let almanac: Almanac = input.parse()?;
 
-        let seeds: Vec<Seed> = read_seeds(first_line).ok_or(AocErrorDay05::SeedsParse(
-                "Failed to parse seeds".to_string(),
-        ))?;
-        debug!("seeds: {:?}", seeds);
+        almanac.locations()
+               .min()
+               .ok_or(AocErrorDay05::MinFailure("Failed to find minimum after seed mapping".to_string()))
+}
 
-        let maps: Vec<Map> = it_chunk.map(Map::from_str)
-                                     .collect();
+/// `process`, with the maps composed into one function first.
+/// (The seed ranges are then cut once, at the composed function's boundaries.)
+pub fn process_composed(input: &str) -> Result<i64, AocErrorDay05> {
+        let almanac: Almanac = input.parse()?;
 
-        seeds.iter()
-                .map(|seed| {
-                        maps.iter().fold(seed.val, |acc, map| {
-                                let temp = map.val_only_passthrough(acc);
-                                info!(?acc, ?temp);
-                                temp
-                        })
-                })
-                .min()
-                .ok_or(AocErrorDay05::MinFailure(
-                        "Failed to find minimum after seed mapping".to_string(),
-                ))
+        almanac.function()
+               .eval_ranges(&almanac.seeds)
+               .min()
+               .ok_or(AocErrorDay05::MinFailure("Failed to find minimum after seed mapping".to_string()))
 }
 
-/// Read a single line string and extract seed values.
-fn read_seeds(line: &str) -> Option<Vec<Seed>> {
-        let Some(_) = RE_SEEDS.captures(line)
-        else {
-                return None;
+/// `process`, searching from the lowest location instead:
+/// bisect for the smallest `l` such that some seed reaches a location in `lo..=l`.
+pub fn process_inverse(input: &str) -> Result<i64, AocErrorDay05> {
+        let almanac: Almanac = input.parse()?;
+        if almanac.seeds.is_empty() {
+                return Err(AocErrorDay05::MinFailure("No seeds to search from".to_string()));
+        }
+        let f = almanac.function();
+
+        // every location is a seed left alone, or lies in some bump's image
+        let images = f.bumps()
+                      .iter()
+                      .map(|b| (b.range.start + b.offset, b.range.end + b.offset));
+        let (floor, ceil) =
+                almanac.seeds
+                       .ranges()
+                       .iter()
+                       .map(|r| (r.start, r.end))
+                       .chain(images)
+                       .reduce(|(lo, hi), (s, e)| (lo.min(s), hi.max(e)))
+                       .ok_or(AocErrorDay05::MinFailure("No seeds to search from".to_string()))?;
+        let reached = |below: i64| {
+                !f.preimage(&RangeSet::from(floor..below))
+                  .intersection(&almanac.seeds)
+                  .is_empty()
         };
 
-        let mut out_seeds: Vec<Seed> = Vec::new();
-        for (_, [base, offset]) in RE_NUM_PAIR.captures_iter(line)
-                                              .map(|c| c.extract())
-        {
-                let base = base.parse::<i64>()
-                               .expect("base_val parse failure");
-                let offset = offset.parse::<i64>()
-                                   .expect("offset_val parse failure");
-
-                for val in base..(base + offset) {
-                        out_seeds.push(Seed::new(val));
+        // invariant: `reached(hi)`, and not `reached(lo)`
+        let (mut lo, mut hi) = (floor, ceil);
+        while hi - lo > 1 {
+                let mid = lo + (hi - lo) / 2;
+                if reached(mid) {
+                        hi = mid;
+                }
+                else {
+                        lo = mid;
                 }
         }
-
-        Some(out_seeds)
+        debug!(location = lo, "lowest location reached");
+        Ok(lo)
 }
 
-/// input & output values and 'range-bumps' that may modify an object
-/// PERF: replace Strings with &str referencing static strings.
-#[derive(Debug, PartialEq, Eq, Constructor)]
-struct Map {
-        inp:   String,
-        out:   String,
-        rmaps: Vec<RangeBump>,
-}
-
-impl Map {
-        /// Passes a value in and passes it back, incremented if it matches any of the ranges.
-        /// With**out** validating input kind or relaying output kind.
-        fn val_only_passthrough(&self, val: i64) -> i64 {
-                self.rmaps
-                    .iter()
-                    .filter_map(|rmap| rmap.try_bump(val))
-                    .next()
-                    .unwrap_or(val)
-        }
-
-        // Populate a map from a contiguous chunk of map string data.
-        fn from_str(chunk: &str) -> Self {
-                let mut lines = chunk.lines();
-                let mut rmaps: Vec<RangeBump> = Vec::new();
-                let first_line = lines.next()
-                                      .expect("empty chunk");
-                trace!("first_line: {:?}", first_line);
-
-                let caps = RE_A_TO_B.captures(first_line)
-                                    .expect("invalid map header");
-                let inp = caps.name("input")
-                              .expect("invalid 'input' map header")
-                              .as_str()
-                              .to_string();
-                let out = caps.name("output")
-                              .expect("invalid 'output' map header")
-                              .as_str()
-                              .to_string();
-                lines.for_each(|line| {
-                             let caps = RE_VAL_MAP.captures(line)
-                                                  .expect("invalid map line");
-                             let in_start = caps.name("instart")
-                                                .expect("in_start")
-                                                .as_str()
-                                                .parse::<i64>()
-                                                .expect("instart parse failure");
-                             let out_start = caps.name("outstart")
-                                                 .expect("outstart")
-                                                 .as_str()
-                                                 .parse::<i64>()
-                                                 .expect("outstart parse failure");
-                             let length = caps.name("length")
-                                              .expect("length")
-                                              .as_str()
-                                              .parse::<i64>()
-                                              .expect("length parse failure");
-
-                             rmaps.push(RangeBump::new(out_start - in_start,
-                                                       in_start..(in_start + length)));
-                     });
-
-                Self::new(inp, out, rmaps)
-        }
-}
 // let caps = re.captures(hay).unwrap();
 // assert_eq!(caps.get(0).unwrap().as_str(), "'Citizen Kane' (1941)");
 // assert_eq!(caps.name("title").unwrap().as_str(), "Citizen Kane");
@@ -192,38 +129,6 @@ impl Map {
 // assert_eq!(&caps["title"], "Citizen Kane");
 // assert_eq!(&caps["year"], "1941");
 
-/// A range of values and the amount they are bumped by.
-#[derive(Debug, PartialEq, Eq, Constructor)]
-struct RangeBump {
-        offset: i64,
-        range:  Range<i64>,
-}
-
-impl RangeBump {
-        /// Returns true if the given value is in the range.
-        /// If so, the offset is applied to the value.
-        fn try_bump(&self, val: i64) -> Option<i64> {
-                debug!(?val, ?self);
-                if self.range.contains(&val) {
-                        Some(val + self.offset)
-                }
-                else {
-                        None
-                }
-        }
-}
-
-/// Now just a seed wrapper ... not really needed; holdover cruft
-///
-/// PERF: there ought to be a way to write a single static string that they can all reference.
-/// Or perhaps a collection of them, they can reference from...
-/// For now, the number of items is so small that I'll just use a whole string, but this is
-/// expensive.
-#[derive(Debug, PartialEq, Eq, Constructor)]
-struct Seed {
-        val: i64,
-}
-
 #[cfg(test)]
 mod tests {
         use indoc::indoc;
@@ -232,7 +137,7 @@ mod tests {
 
         #[test]
         fn test_process_example() -> Result<()> {
-                tracing_subscriber::fmt::init(); // RUST_LOG=...
+                let _ = tracing_subscriber::fmt::try_init(); // RUST_LOG=...
 
                 let input = indoc! {"
                         seeds: 79 14 55 13
@@ -271,19 +176,59 @@ mod tests {
                 "};
                 let expected = 46;
                 assert_eq!(process(input)?, expected);
+                assert_eq!(process_composed(input)?, expected);
+                assert_eq!(process_inverse(input)?, expected);
+
+                // no seeds, no lowest location (even though the maps cover some)
+                let no_seeds = "seeds: \n\nseed-to-soil map:\n50 98 2";
+                assert!(matches!(process(no_seeds), Err(AocErrorDay05::MinFailure(_))));
+                assert!(matches!(process_composed(no_seeds),
+                                 Err(AocErrorDay05::MinFailure(_))));
+                assert!(matches!(process_inverse(no_seeds), Err(AocErrorDay05::MinFailure(_))));
+                Ok(())
+        }
+
+        /// Where one seed lands, a map at a time.
+        fn land(almanac: &Almanac, seed: i64) -> i64 {
+                almanac.maps
+                       .iter()
+                       .fold(seed, |acc, map| map.val_only_passthrough(acc))
+        }
+
+        /// Moving ranges must land exactly where moving each value would.
+        #[test]
+        fn test_ranges_agree_with_values() -> Result<()> {
+                let almanac: Almanac = include_str!("../input2.txt").parse()?;
+                // every seed start, as a one-value range, and a span crossing many bumps
+                let probes = almanac.seeds
+                                    .ranges()
+                                    .iter()
+                                    .map(|r| r.start..r.start + 1)
+                                    .chain(std::iter::once(0..20_000));
+                for probe in probes {
+                        let by_range = almanac.maps
+                                              .iter()
+                                              .fold(RangeSet::from(probe.clone()), |acc, map| {
+                                                      map.range_passthrough(&acc)
+                                              });
+                        let by_value: RangeSet<i64> =
+                                probe.map(|seed| land(&almanac, seed)..land(&almanac, seed) + 1)
+                                     .collect();
+                        assert_eq!(by_range, by_value);
+                }
                 Ok(())
         }
 
         /// This test's expected value is to be populated after
         /// verification of solution.
         /// (useful for future refactors and perfs)
-        /// NOTE: `#[ignore]` is set for this test by default.
-        #[ignore]
         #[test]
         fn test_process_problem_input() -> Result<()> {
-                let file_input = include_str!("../input1.txt");
+                let file_input = include_str!("../input2.txt");
                 let expected = 2520479;
                 assert_eq!(process(file_input)?, expected);
+                assert_eq!(process_composed(file_input)?, expected);
+                assert_eq!(process_inverse(file_input)?, expected);
                 Ok(())
         }
 }
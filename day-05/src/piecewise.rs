@@ -0,0 +1,275 @@
+//! A chain of almanac maps, composed into one function.
+//!
+//! Every map shifts values by a constant within each of its ranges, and so does any chain of them.
+//! `Piecewise` holds those ranges (as `RangeBump`s) sorted and non-overlapping;
+//! values outside all of them map to themselves.
+//! Looking a value up is then one binary search, however many maps went into it.
+//!
+//! ## Normal form
+//! - bumps sorted by start and disjoint
+//! - no zero offsets (that's just the identity)
+//! - no two touching bumps with the same offset
+//!
+//! So two chains that compute the same function come out equal, and `Display` output diffs cleanly.
+//!
+//! ## Composing
+//! The composed offset only changes where the first function's does,
+//! or where the first function's output crosses a boundary of the second.
+//! So: collect those cut points, and read the offset off at each.
+
+use std::fmt;
+
+use tracing::debug;
+
+use crate::{
+        almanac::{Map, RangeBump},
+        range_math::{RangeMath, RangeSet},
+};
+
+/// Piecewise offset function on `i64`, in normal form.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Piecewise {
+        bumps: Vec<RangeBump>,
+}
+
+impl Piecewise {
+        pub fn identity() -> Self {
+                Self::default()
+        }
+
+        /// The non-identity pieces, sorted by start.
+        pub fn bumps(&self) -> &[RangeBump] {
+                &self.bumps
+        }
+
+        /// How far `val` moves.
+        pub fn offset_at(&self, val: i64) -> i64 {
+                let i = self.bumps
+                            .partition_point(|b| b.range.end <= val);
+                self.bumps
+                    .get(i)
+                    .filter(|b| b.range.contains(&val))
+                    .map_or(0, |b| b.offset)
+        }
+
+        pub fn eval(&self, val: i64) -> i64 {
+                val + self.offset_at(val)
+        }
+
+        /// One map as a function. (Overlapping bumps resolve as in the map: first wins.)
+        pub fn from_map(map: &Map) -> Self {
+                let cuts = map.rmaps
+                              .iter()
+                              .flat_map(|b| [b.range.start, b.range.end])
+                              .collect();
+                Self::from_cuts(cuts, |val| map.val_only_passthrough(val) - val)
+        }
+
+        /// All the maps, applied in order.
+        pub fn chain<'a>(maps: impl IntoIterator<Item = &'a Map>) -> Self {
+                maps.into_iter()
+                    .fold(Self::identity(), |acc, map| {
+                            acc.compose(&Self::from_map(map))
+                    })
+        }
+
+        /// `then` applied after `self`.
+        pub fn compose(&self, then: &Self) -> Self {
+                let mut cuts: Vec<i64> = self.boundaries().collect();
+                for y in then.boundaries() {
+                        cuts.extend(self.preimages(y));
+                }
+                let out = Self::from_cuts(cuts, |val| {
+                        let mid = self.eval(val);
+                        mid + then.offset_at(mid) - val
+                });
+                debug!(first = self.bumps.len(),
+                       then = then.bumps.len(),
+                       composed = out.bumps.len());
+                out
+        }
+
+        /// Every value of `vals`, moved.
+        pub fn eval_ranges(&self, vals: &RangeSet<i64>) -> RangeSet<i64> {
+                let mut out = RangeSet::new();
+                for r in vals.ranges() {
+                        let mut cuts: Vec<i64> = self.boundaries()
+                                                     .filter(|c| r.contains(c))
+                                                     .collect();
+                        cuts.push(r.start);
+                        cuts.push(r.end);
+                        cuts.sort_unstable();
+                        cuts.dedup();
+                        for w in cuts.windows(2) {
+                                let offset = self.offset_at(w[0]);
+                                out.insert(w[0] + offset..w[1] + offset);
+                        }
+                }
+                out
+        }
+
+        /// Every value sent into `targets`.
+        pub fn preimage(&self, targets: &RangeSet<i64>) -> RangeSet<i64> {
+                let mut out = RangeSet::new();
+                let mut moved = RangeSet::new();
+                for b in &self.bumps {
+                        moved.insert(b.range.clone());
+                        for t in targets.ranges() {
+                                out.extend(t.shift(-b.offset)
+                                            .intersect(&b.range));
+                        }
+                }
+                out.union(&targets.difference(&moved))
+        }
+
+        fn boundaries(&self) -> impl Iterator<Item = i64> + '_ {
+                self.bumps
+                    .iter()
+                    .flat_map(|b| [b.range.start, b.range.end])
+        }
+
+        /// Values `self` sends to `y`.
+        fn preimages(&self, y: i64) -> impl Iterator<Item = i64> + '_ {
+                let unmoved = (self.offset_at(y) == 0).then_some(y);
+                let moved = self.bumps
+                                .iter()
+                                .map(move |b| y - b.offset)
+                                .filter(move |x| self.offset_at(*x) != 0 && self.eval(*x) == y);
+                unmoved.into_iter()
+                       .chain(moved)
+        }
+
+        /// Normal form, given every point where the offset may change.
+        fn from_cuts(mut cuts: Vec<i64>, offset_at: impl Fn(i64) -> i64) -> Self {
+                cuts.sort_unstable();
+                cuts.dedup();
+                let mut bumps: Vec<RangeBump> = Vec::new();
+                for w in cuts.windows(2) {
+                        let offset = offset_at(w[0]);
+                        if offset == 0 {
+                                continue;
+                        }
+                        match bumps.last_mut() {
+                                Some(last) if last.offset == offset && last.range.end == w[0] => {
+                                        last.range.end = w[1];
+                                },
+                                _ => bumps.push(RangeBump::new(offset, w[0]..w[1])),
+                        }
+                }
+                Self { bumps }
+        }
+}
+
+/// One `<outstart> <instart> <length>` line per bump, as in the almanac.
+impl fmt::Display for Piecewise {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for b in &self.bumps {
+                        writeln!(f, "{}", b)?;
+                }
+                Ok(())
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use indoc::indoc;
+
+        use super::*;
+        use crate::custom_error::AocErrorDay05;
+
+        const EXAMPLE_MAPS: &str = indoc! {"
+                seed-to-soil map:
+                50 98 2
+                52 50 48
+
+                soil-to-fertilizer map:
+                0 15 37
+                37 52 2
+                39 0 15
+
+                fertilizer-to-water map:
+                49 53 8
+                0 11 42
+                42 0 7
+                57 7 4
+
+                water-to-light map:
+                88 18 7
+                18 25 70
+
+                light-to-temperature map:
+                45 77 23
+                81 45 19
+                68 64 13
+
+                temperature-to-humidity map:
+                0 69 1
+                1 0 69
+
+                humidity-to-location map:
+                60 56 37
+                56 93 4
+        "};
+
+        fn example_maps() -> Result<Vec<Map>, AocErrorDay05> {
+                EXAMPLE_MAPS.split("\n\n")
+                            .map(str::parse)
+                            .collect()
+        }
+
+        #[test]
+        fn test_chain_matches_fold() -> Result<(), AocErrorDay05> {
+                let maps = example_maps()?;
+                let f = Piecewise::chain(&maps);
+                for seed in -5..150 {
+                        let folded = maps.iter()
+                                         .fold(seed, |acc, map| map.val_only_passthrough(acc));
+                        assert_eq!(f.eval(seed), folded, "seed {}", seed);
+                }
+                assert_eq!([79, 14, 55, 13].map(|s| f.eval(s)), [82, 43, 86, 35]);
+                Ok(())
+        }
+
+        #[test]
+        fn test_normal_form() -> Result<(), AocErrorDay05> {
+                let f = Piecewise::chain(&example_maps()?);
+                for pair in f.bumps().windows(2) {
+                        assert!(pair[0].range.end <= pair[1].range.start);
+                        assert!(pair[0].range.end < pair[1].range.start
+                                || pair[0].offset != pair[1].offset);
+                }
+                assert!(f.bumps()
+                         .iter()
+                         .all(|b| b.offset != 0 && !b.range.is_empty()));
+
+                // swapping two blocks twice composes to nothing
+                let swap: Map = "a-to-b map:\n10 0 5\n0 10 5".parse()?;
+                assert_eq!(Piecewise::chain([&swap, &swap]), Piecewise::identity());
+                // touching bumps with one offset merge
+                let split: Map = "a-to-b map:\n13 3 2\n10 0 3".parse()?;
+                let whole: Map = "a-to-b map:\n10 0 5".parse()?;
+                assert_eq!(Piecewise::from_map(&split), Piecewise::from_map(&whole));
+                Ok(())
+        }
+
+        #[test]
+        fn test_display_reparses() -> Result<(), AocErrorDay05> {
+                let f = Piecewise::chain(&example_maps()?);
+                let again: Map = format!("seed-to-location map:\n{}", f).trim_end()
+                                                                        .parse()?;
+                assert_eq!(Piecewise::from_map(&again), f);
+                Ok(())
+        }
+
+        #[test]
+        fn test_eval_ranges() -> Result<(), AocErrorDay05> {
+                let f = Piecewise::chain(&example_maps()?);
+                let seeds = RangeSet::from_iter([79..93, 55..68]);
+                let moved = f.eval_ranges(&seeds);
+                assert_eq!(moved.min(), Some(46));
+                for seed in (79..93).chain(55..68) {
+                        assert!(moved.contains(&f.eval(seed)));
+                }
+                Ok(())
+        }
+}
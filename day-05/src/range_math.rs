@@ -1,7 +1,7 @@
 //! # Library to define range arithmetic.
 //!
-//! ## e.g. <conceptual>
-//!
+//! ## e.g.
+//! ```text
 //! (0..5).collapse_(2..6) = [(0..6)]
 //! (0..5).split____(2..6) = [(0..2), (2..5), (5..6)]
 //! (0..5).intersect(2..6) = [(2..5)]
@@ -11,16 +11,18 @@
 //! (0..5).split____(5..6) = [(0..5), (5..6)]
 //! (0..5).intersect(5..6) = []
 //! (0..5).left_take(5..6) = [(0..5), (5..6)]
-//!
-//! ^ possibly try variants, but seems less performant ... maybe more if not allocating
-//!   ... maybe it could return a tuple or array with Some/None...
-//!
-//! # also <conceptual>
-//! give it an extensions that does the offset addition for the ranges
-//! ^ maybe that should be in main library...
+//! ```
+//! (`intersect` gives at most one range, so it's an `Option` rather than a list.)
 //!
 //! ## implementation
-//! extend Range object ... (?)
+//! `RangeMath` extends `Range<T>` for any `Ord + Copy` index. Ranges are half-open;
+//! empty ranges vanish from every result, and results come back sorted by start
+//! (bar `left_take`, which puts `self` first: `(3..4).left_take(0..10)` is
+//! `[(3..4), (0..3), (4..10)]`).
+//! Touching ranges (`0..5`, `5..6`) do *not* overlap, so they don't collapse.
+//!
+//! `RangeSet` is the normalized form: sorted, disjoint, with touching ranges merged,
+//! so two sets covering the same values are equal.
 //!
 //! ## Next Step
 //! I want to use the range methods to create RangeBump methods
@@ -31,3 +33,290 @@
 //! Skip the ranges entirely and just model the boundaries of i64, with offset in each
 //! Need a special `None`` value for adding seeds so we only get 'populated' ranges
 //! Actually ... that might be simplest
+
+use std::ops::{Add, Range};
+
+/// Interval algebra on half-open ranges.
+pub trait RangeMath<T>: Sized {
+        /// Whether the ranges share at least one value.
+        fn overlaps(&self, other: &Self) -> bool;
+
+        /// One range if they overlap, else both (sorted).
+        fn collapse(&self, other: &Self) -> Vec<Self>;
+
+        /// Everything either covers, cut at every boundary of both.
+        fn split(&self, other: &Self) -> Vec<Self>;
+
+        /// Values in both.
+        fn intersect(&self, other: &Self) -> Option<Self>;
+
+        /// `self` whole, then whatever of `other` lies outside it.
+        fn left_take(&self, other: &Self) -> Vec<Self>;
+
+        /// Values of `self` not in `other`: up to two pieces.
+        fn difference(&self, other: &Self) -> Vec<Self>;
+
+        /// Both ends moved by `by`.
+        fn shift(&self, by: T) -> Self
+                where T: Add<Output = T>;
+}
+
+impl<T: Ord + Copy> RangeMath<T> for Range<T> {
+        fn overlaps(&self, other: &Self) -> bool {
+                self.intersect(other)
+                    .is_some()
+        }
+
+        fn collapse(&self, other: &Self) -> Vec<Self> {
+                if self.overlaps(other) {
+                        return vec![self.start.min(other.start)..self.end.max(other.end)];
+                }
+                let mut out: Vec<Self> = [self.clone(), other.clone()].into_iter()
+                                                                      .filter(|r| !r.is_empty())
+                                                                      .collect();
+                out.sort_by_key(|r| r.start);
+                out
+        }
+
+        fn split(&self, other: &Self) -> Vec<Self> {
+                let mut cuts: Vec<T> = [self, other].into_iter()
+                                                    .filter(|r| !r.is_empty())
+                                                    .flat_map(|r| [r.start, r.end])
+                                                    .collect();
+                cuts.sort();
+                cuts.dedup();
+                cuts.windows(2)
+                    .map(|w| w[0]..w[1])
+                    .filter(|piece| {
+                            // a piece lies wholly inside or outside each range
+                            [self, other].iter()
+                                         .any(|r| r.contains(&piece.start))
+                    })
+                    .collect()
+        }
+
+        fn intersect(&self, other: &Self) -> Option<Self> {
+                let out = self.start.max(other.start)..self.end.min(other.end);
+                (!out.is_empty()).then_some(out)
+        }
+
+        fn left_take(&self, other: &Self) -> Vec<Self> {
+                let mut out: Vec<Self> = Vec::with_capacity(3);
+                if !self.is_empty() {
+                        out.push(self.clone());
+                }
+                out.extend(other.difference(self));
+                out
+        }
+
+        fn difference(&self, other: &Self) -> Vec<Self> {
+                if !self.overlaps(other) {
+                        return if self.is_empty() {
+                                vec![]
+                        }
+                        else {
+                                vec![self.clone()]
+                        };
+                }
+                [self.start..other.start, other.end..self.end].into_iter()
+                                                              .filter(|r| !r.is_empty())
+                                                              .collect()
+        }
+
+        fn shift(&self, by: T) -> Self
+                where T: Add<Output = T> {
+                (self.start + by)..(self.end + by)
+        }
+}
+
+/// Normalized set of values: sorted, disjoint, non-touching ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+        ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for RangeSet<T> {
+        fn default() -> Self {
+                Self { ranges: Vec::new() }
+        }
+}
+
+impl<T: Ord + Copy> RangeSet<T> {
+        pub fn new() -> Self {
+                Self::default()
+        }
+
+        /// The ranges, sorted by start.
+        pub fn ranges(&self) -> &[Range<T>] {
+                &self.ranges
+        }
+
+        pub fn is_empty(&self) -> bool {
+                self.ranges.is_empty()
+        }
+
+        pub fn contains(&self, val: &T) -> bool {
+                let i = self.ranges
+                            .partition_point(|r| r.end <= *val);
+                self.ranges
+                    .get(i)
+                    .is_some_and(|r| r.contains(val))
+        }
+
+        /// Smallest value in the set.
+        pub fn min(&self) -> Option<T> {
+                self.ranges
+                    .first()
+                    .map(|r| r.start)
+        }
+
+        /// Add a range, merging it with any it overlaps or touches.
+        pub fn insert(&mut self, range: Range<T>) {
+                if range.is_empty() {
+                        return;
+                }
+                // everything ending before `range` starts stays left; starting after it ends stays right
+                let lo = self.ranges
+                             .partition_point(|r| r.end < range.start);
+                let hi = self.ranges
+                             .partition_point(|r| r.start <= range.end);
+                let merged = match self.ranges[lo..hi] {
+                        [] => range,
+                        ref touched =>
+                                touched[0].start
+                                          .min(range.start)
+                                ..touched[touched.len() - 1].end
+                                                            .max(range.end),
+                };
+                self.ranges
+                    .splice(lo..hi, [merged]);
+        }
+
+        pub fn union(&self, other: &Self) -> Self {
+                let mut out = self.clone();
+                for r in &other.ranges {
+                        out.insert(r.clone());
+                }
+                out
+        }
+
+        pub fn intersection(&self, other: &Self) -> Self {
+                // both sorted: walk them together
+                let (mut i, mut j) = (0, 0);
+                let mut ranges = Vec::new();
+                while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+                        ranges.extend(a.intersect(b));
+                        if a.end <= b.end {
+                                i += 1;
+                        }
+                        else {
+                                j += 1;
+                        }
+                }
+                Self { ranges }
+        }
+
+        pub fn difference(&self, other: &Self) -> Self {
+                let mut ranges = Vec::new();
+                for r in &self.ranges {
+                        let mut rest = vec![r.clone()];
+                        for cut in other.ranges
+                                        .iter()
+                                        .filter(|c| c.overlaps(r))
+                        {
+                                rest = rest.iter()
+                                           .flat_map(|piece| piece.difference(cut))
+                                           .collect();
+                        }
+                        ranges.extend(rest);
+                }
+                Self { ranges }
+        }
+
+        /// Values of `within` not in the set.
+        pub fn complement(&self, within: Range<T>) -> Self {
+                Self::from(within).difference(self)
+        }
+}
+
+impl<T: Ord + Copy> FromIterator<Range<T>> for RangeSet<T> {
+        fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+                let mut set = Self::new();
+                set.extend(iter);
+                set
+        }
+}
+
+impl<T: Ord + Copy> From<Range<T>> for RangeSet<T> {
+        fn from(range: Range<T>) -> Self {
+                let mut set = Self::new();
+                set.insert(range);
+                set
+        }
+}
+
+impl<T: Ord + Copy> Extend<Range<T>> for RangeSet<T> {
+        fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, iter: I) {
+                for r in iter {
+                        self.insert(r);
+                }
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use super::*;
+
+        #[test]
+        fn test_doc_examples_overlapping() {
+                assert_eq!((0..5).collapse(&(2..6)), vec![0..6]);
+                assert_eq!((0..5).split(&(2..6)), vec![0..2, 2..5, 5..6]);
+                assert_eq!((0..5).intersect(&(2..6)), Some(2..5));
+                assert_eq!((0..5).left_take(&(2..6)), vec![0..5, 5..6]);
+        }
+
+        #[test]
+        fn test_doc_examples_touching() {
+                assert_eq!((0..5).collapse(&(5..6)), vec![0..5, 5..6]);
+                assert_eq!((0..5).split(&(5..6)), vec![0..5, 5..6]);
+                assert_eq!((0..5).intersect(&(5..6)), None);
+                assert_eq!((0..5).left_take(&(5..6)), vec![0..5, 5..6]);
+        }
+
+        #[test]
+        fn test_nested_disjoint_and_empty() {
+                assert_eq!((0..10).split(&(3..4)), vec![0..3, 3..4, 4..10]);
+                // `self` first, not sorted
+                assert_eq!((3..4).left_take(&(0..10)), vec![3..4, 0..3, 4..10]);
+                assert_eq!((0..10).difference(&(3..4)), vec![0..3, 4..10]);
+                assert_eq!((0..2).split(&(5..6)), vec![0..2, 5..6]);
+                assert_eq!((5..6).collapse(&(0..2)), vec![0..2, 5..6]);
+                assert_eq!((3..3).collapse(&(0..2)), vec![0..2]);
+                assert_eq!((0..2).difference(&(5..6)), vec![0..2]);
+                assert_eq!((-3i64..2).shift(10), 7..12);
+        }
+
+        #[test]
+        fn test_range_set() {
+                let set: RangeSet<i64> = [5..8, 0..2, 2..3, 7..10, 20..20].into_iter()
+                                                                          .collect();
+                assert_eq!(set.ranges(), &[0..3, 5..10]);
+                assert!(set.contains(&9) && !set.contains(&3) && !set.contains(&10));
+                assert_eq!(set.min(), Some(0));
+
+                let other: RangeSet<i64> = [2..6, 9..12].into_iter()
+                                                        .collect();
+                assert_eq!(set.union(&other).ranges(), std::slice::from_ref(&(0..12)));
+                assert_eq!(set.intersection(&other)
+                              .ranges(),
+                           &[2..3, 5..6, 9..10]);
+                assert_eq!(set.difference(&other)
+                              .ranges(),
+                           &[0..2, 6..9]);
+                assert_eq!(set.complement(-5..15)
+                              .ranges(),
+                           &[-5..0, 3..5, 10..15]);
+                assert!(RangeSet::<i64>::new().complement(0..0)
+                                              .is_empty());
+        }
+}
@@ -0,0 +1,285 @@
+//! Race variants: what holding the button does, as a pluggable rule.
+//!
+//! The puzzle's boat gains 1 mm/ms of speed per ms held, then coasts for the rest of the race.
+//! A `Charge` rule replaces that: it gives the speed after holding for `hold` ms,
+//! and (by default) the boat covers `speed * (time - hold)`.
+//! Rules that change the coasting too (like `Drag`) override `distance` instead.
+//!
+//! ## Solving
+//! No closed form, so the solver leans on shape: distance may start flat (e.g. no speed until a
+//! few ms held), then must rise strictly to a peak and never rise again after it (flat stretches
+//! are fine from the peak on, e.g. a flat top or a tail of zeros). Then
+//! - the flat start is stepped over, one hold at a time
+//! - the peak is where the distance next stops rising (a bisection on `d(h + 1) <= d(h)`)
+//! - the winning holds run from the first win left of the peak to the last win right of it,
+//!   each a bisection over a monotone side
+//!
+//! `race::winning_holds` is the exact, closed-form version for the stock rule.
+//!
+//! ## e.g.
+//! ```text
+//! Polynomial::stock()                          h * (t - h)      (the puzzle)
+//! Polynomial::new(vec![0, 0, 1])               h² * (t - h)     (charge rate grows as you hold)
+//! Capped::new(Polynomial::stock(), 40)         speed tops out at 40 mm/ms
+//! Drag::new(Polynomial::stock(), 2)            loses 2 mm/ms of speed per ms coasting
+//! |hold: u128| Some(3 * hold)                  any closure from hold to speed
+//! ```
+
+use std::ops::RangeInclusive;
+
+use derive_more::Constructor;
+use tracing::trace;
+
+use crate::custom_error::AocErrorDay06;
+
+/// What holding the button does.
+/// Any `None` means "too big for a `u128`", which beats any record.
+pub trait Charge {
+        /// Speed (mm/ms) after holding for `hold` ms.
+        fn speed(&self, hold: u128) -> Option<u128>;
+
+        /// Distance (mm) after holding for `hold` ms of a `time` ms race.
+        fn distance(&self, time: u128, hold: u128) -> Option<u128> {
+                self.speed(hold)?
+                    .checked_mul(time.checked_sub(hold)?)
+        }
+}
+
+impl<F: Fn(u128) -> Option<u128>> Charge for F {
+        fn speed(&self, hold: u128) -> Option<u128> {
+                self(hold)
+        }
+}
+
+/// Speed as a polynomial in the hold: `coeffs[i] * hold^i`, summed.
+#[derive(Debug, Clone, PartialEq, Eq, Constructor)]
+pub struct Polynomial {
+        pub coeffs: Vec<u128>,
+}
+
+impl Polynomial {
+        /// 1 mm/ms per ms held: the puzzle's rule.
+        pub fn stock() -> Self {
+                Self::new(vec![0, 1])
+        }
+}
+
+impl Charge for Polynomial {
+        fn speed(&self, hold: u128) -> Option<u128> {
+                // Horner's rule
+                self.coeffs
+                    .iter()
+                    .rev()
+                    .try_fold(0u128, |acc, &c| {
+                            acc.checked_mul(hold)?
+                               .checked_add(c)
+                    })
+        }
+}
+
+/// `rule`, with speed capped at `cap`.
+#[derive(Debug, Clone, PartialEq, Eq, Constructor)]
+pub struct Capped<C> {
+        pub rule: C,
+        pub cap:  u128,
+}
+
+impl<C: Charge> Charge for Capped<C> {
+        fn speed(&self, hold: u128) -> Option<u128> {
+                Some(self.rule
+                         .speed(hold)
+                         .map_or(self.cap, |s| s.min(self.cap)))
+        }
+}
+
+/// `rule`, but coasting loses `decel` mm/ms of speed every ms (stopping at zero).
+#[derive(Debug, Clone, PartialEq, Eq, Constructor)]
+pub struct Drag<C> {
+        pub rule:  C,
+        pub decel: u128,
+}
+
+impl<C: Charge> Charge for Drag<C> {
+        fn speed(&self, hold: u128) -> Option<u128> {
+                self.rule.speed(hold)
+        }
+
+        /// `v + (v - a) + (v - 2a) + ...`, for the coasting ms before the speed runs out.
+        fn distance(&self, time: u128, hold: u128) -> Option<u128> {
+                let v = self.speed(hold)?;
+                let coast = time.checked_sub(hold)?;
+                if self.decel == 0 {
+                        return v.checked_mul(coast);
+                }
+                let moving = coast.min(v.div_ceil(self.decel));
+                let lost = self.decel
+                               .checked_mul(moving)?
+                               .checked_mul(moving.saturating_sub(1))?
+                           / 2;
+                v.checked_mul(moving)?
+                 .checked_sub(lost)
+        }
+}
+
+/// Orders distances, with `None` (too big to hold) above every number.
+fn reach(distance: Option<u128>) -> (bool, u128) {
+        (distance.is_none(), distance.unwrap_or(0))
+}
+
+/// Whether holding for `hold` ms beats `record`.
+pub fn beats(rule: &(impl Charge + ?Sized), time: u128, record: u128, hold: u128) -> bool {
+        reach(rule.distance(time, hold)) > (false, record)
+}
+
+/// Hold with the furthest distance (the first one, if the top is flat).
+pub fn peak(rule: &(impl Charge + ?Sized), time: u128) -> u128 {
+        let d = |h: u128| reach(rule.distance(time, h));
+        // a flat start would read as a peak at 0, so step past it first
+        let flat = (0..time).find(|&h| d(h + 1) != d(h))
+                            .unwrap_or(time);
+        if flat == time || d(flat + 1) < d(flat) {
+                // flat all the way, or flat then falling: the top is where it starts
+                return 0;
+        }
+        // past the peak the distance can fall or stay flat, but never rise
+        let stops = |h: u128| d(h + 1) <= d(h);
+        // first `h` in `flat..time` where the distance stops rising, else `time` itself
+        let (mut lo, mut hi) = (flat, time);
+        while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if stops(mid) {
+                        hi = mid;
+                }
+                else {
+                        lo = mid + 1;
+                }
+        }
+        lo
+}
+
+/// Every hold that beats the record, under `rule`.
+pub fn winning_holds(rule: &(impl Charge + ?Sized),
+                     time: u128,
+                     record: u128)
+                     -> Result<RangeInclusive<u128>, AocErrorDay06> {
+        let top = peak(rule, time);
+        if !beats(rule, time, record, top) {
+                return Err(AocErrorDay06::Unwinnable { time, record });
+        }
+        // first win in `0..=top`: wins are monotone rising there
+        let (mut lo, mut hi) = (0, top);
+        while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if beats(rule, time, record, mid) {
+                        hi = mid;
+                }
+                else {
+                        lo = mid + 1;
+                }
+        }
+        let first = lo;
+        // last win in `top..=time`: wins are monotone falling there
+        let (mut lo, mut hi) = (top, time);
+        while lo < hi {
+                let mid = lo + (hi - lo).div_ceil(2);
+                if beats(rule, time, record, mid) {
+                        lo = mid;
+                }
+                else {
+                        hi = mid - 1;
+                }
+        }
+        trace!(time, record, top, first, last = lo, "winning window");
+        Ok(first..=lo)
+}
+
+/// How many holds beat the record, under `rule`.
+pub fn ways_to_win(rule: &(impl Charge + ?Sized),
+                   time: u128,
+                   record: u128)
+                   -> Result<u128, AocErrorDay06> {
+        let holds = winning_holds(rule, time, record)?;
+        Ok(holds.end() - holds.start() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+        use super::*;
+        use crate::race;
+
+        fn brute_force(rule: &dyn Charge, time: u128, record: u128) -> u128 {
+                (0..=time).filter(|&hold| beats(rule, time, record, hold))
+                          .count() as u128
+        }
+
+        /// Every record from 0 to just past the best, for every race up to `max_time`.
+        fn check_against_brute_force(rule: &dyn Charge, max_time: u128) {
+                for time in 0..=max_time {
+                        let best = (0..=time).filter_map(|h| rule.distance(time, h))
+                                             .max()
+                                             .unwrap_or(0);
+                        for record in (0..=best + 1).step_by((best as usize / 200).max(1)) {
+                                let expected = brute_force(rule, time, record);
+                                let found = ways_to_win(rule, time, record).unwrap_or(0);
+                                assert_eq!(found, expected, "race {} {}", time, record);
+                        }
+                }
+        }
+
+        #[test]
+        fn test_stock_matches_closed_form() -> Result<(), AocErrorDay06> {
+                let stock = Polynomial::stock();
+                for (time, record) in [(7, 9),
+                                       (15, 40),
+                                       (30, 200),
+                                       (71530, 940200),
+                                       (60_947_882, 475_213_810_151_650)]
+                {
+                        assert_eq!(winning_holds(&stock, time, record)?,
+                                   race::winning_holds(time, record)?);
+                }
+                check_against_brute_force(&stock, 60);
+                Ok(())
+        }
+
+        #[test]
+        fn test_variants_against_brute_force() {
+                check_against_brute_force(&Polynomial::new(vec![0, 0, 1]), 40);
+                check_against_brute_force(&Polynomial::new(vec![3, 2, 1]), 40);
+                check_against_brute_force(&Capped::new(Polynomial::stock(), 7), 50);
+                check_against_brute_force(&Drag::new(Polynomial::stock(), 2), 50);
+                check_against_brute_force(&Drag::new(Capped::new(Polynomial::new(vec![0, 0, 1]),
+                                                                 30),
+                                                     5),
+                                          50);
+                check_against_brute_force(&|hold: u128| Some(3 * hold), 50);
+                // flat tail: no speed at all past 5 ms held
+                check_against_brute_force(&|hold: u128| Some(if hold > 5 { 0 } else { hold }), 100);
+                // flat start: no speed until 3 ms held
+                check_against_brute_force(&|hold: u128| Some(if hold < 3 { 0 } else { hold }), 50);
+        }
+
+        #[test]
+        fn test_rules() -> Result<(), AocErrorDay06> {
+                // capped at 4 mm/ms in a 10 ms race: holding 4 goes 24, holding 3 goes 21
+                let capped = Capped::new(Polynomial::stock(), 4);
+                assert_eq!(peak(&capped, 10), 4);
+                // the top of a flat tail, not somewhere along it
+                let stalls = |hold: u128| Some(if hold > 5 { 0 } else { hold });
+                assert_eq!(peak(&stalls, 100), 5);
+                assert_eq!(ways_to_win(&stalls, 100, 0)?, 5);
+                // past a flat start: 0 for holds under 3, then 21, 24, 25, 24, ... in 10 ms
+                let delayed = |hold: u128| Some(if hold < 3 { 0 } else { hold });
+                assert_eq!(peak(&delayed, 10), 5);
+                assert_eq!(winning_holds(&delayed, 10, 0)?, 3..=9);
+                assert_eq!(winning_holds(&capped, 10, 21)?, 4..=4);
+                // 6 mm/ms with 2 mm/ms drag: 6 + 4 + 2 over 4 ms of coasting
+                assert_eq!(Drag::new(Polynomial::stock(), 2).distance(10, 6), Some(12));
+                assert!(matches!(ways_to_win(&Polynomial::stock(), 10, 25),
+                                 Err(AocErrorDay06::Unwinnable { .. })));
+                // too fast to count still wins
+                let huge = Polynomial::new(vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+                assert!(beats(&huge, 1000, u128::MAX, 500));
+                Ok(())
+        }
+}
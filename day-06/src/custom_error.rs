@@ -1,16 +1,79 @@
 //! Custom Error type for Day 06 of Advent of Code 2023.
 
-// use miette::Diagnostic;
+use std::fmt;
+
+use aoc_common::spanned::InvalidInput;
+use miette::Diagnostic;
+use num_bigint::BigUint;
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+#[derive(Error, Diagnostic, Debug)]
 pub enum AocErrorDay06 {
         #[error(transparent)]
+        #[diagnostic(code(aoc::io_error))]
         IoError(#[from] std::io::Error),
 
         #[error("Error parsing seeds: {0}")]
+        #[diagnostic(code(aoc::parse_error))]
         ParseError(String),
+
+        #[error(transparent)]
+        #[diagnostic(transparent)]
+        InvalidSheet(Box<InvalidSheet>),
+
+        #[error("Unwinnable race: {time} ms can't beat a record of {record} mm")]
+        #[diagnostic(code(aoc::unwinnable))]
+        Unwinnable { time: u128, record: u128 },
+
+        #[error("Unwinnable race: {time} ms can't beat a record of {record} mm")]
+        #[diagnostic(code(aoc::unwinnable))]
+        UnwinnableBig { time: BigUint, record: BigUint },
         // #[error("Error parsing seeds: {0}")]
         // #[diagnostic(code(aoc::min_unwrap_error))]
         // MinFailure(String),
 }
+
+/// A race sheet that doesn't read as one `Time:` and one `Distance:` record.
+pub type InvalidSheet = InvalidInput<SheetProblem>;
+
+impl From<InvalidSheet> for AocErrorDay06 {
+        fn from(err: InvalidSheet) -> Self {
+                Self::InvalidSheet(Box::new(err))
+        }
+}
+
+/// What's wrong with an `InvalidSheet`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SheetProblem {
+        /// No line with this header.
+        Missing(&'static str),
+        /// Two lines with this header.
+        Duplicate(&'static str),
+        /// A line whose header isn't a record we know.
+        UnknownHeader(String),
+        /// A record line without any numbers.
+        NoNumbers(&'static str),
+        /// Part 1 pairs times with distances column by column.
+        ColumnMismatch { times: usize, dists: usize },
+        /// A number past `u64`.
+        TooBig(&'static str),
+}
+
+impl fmt::Display for SheetProblem {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                        Self::Missing(record) => write!(f, "no `{}:` line", record),
+                        Self::Duplicate(record) => write!(f, "second `{}:` line", record),
+                        Self::UnknownHeader(header) => {
+                                write!(f,
+                                       "unknown record {:?}, expected `Time:` or `Distance:`",
+                                       header)
+                        },
+                        Self::NoNumbers(record) => write!(f, "`{}:` line has no numbers", record),
+                        Self::ColumnMismatch { times, dists } => {
+                                write!(f, "{} times but {} distances", times, dists)
+                        },
+                        Self::TooBig(record) => write!(f, "{} too big for u64", record),
+                }
+        }
+}
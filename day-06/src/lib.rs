@@ -3,7 +3,10 @@
 //! And the architecture of the crate will be hierarchical about `part1_lib.rs` and part2_lib.rs`.
 //! (With redundancy common, and by design, between those two files.)
 
+pub mod charge;
 pub mod custom_error;
+pub mod race;
+pub mod sheet;
 
 pub mod part1_lib;
 pub mod part2_lib;
@@ -2,18 +2,15 @@
 //! `bin > part1.rs` will run this code along with conent of `input1.txt`
 
 use anyhow::Result;
-use derive_more::Constructor;
-use once_cell::sync::Lazy;
 // use rayon::prelude::*;
-use regex::Regex;
 // use std::ops::RangeInclusive;
-use tracing::{debug, info, trace};
+use tracing::info;
 
-use crate::custom_error::AocErrorDay06;
-
-static RE_TIME: Lazy<Regex> = Lazy::new(|| Regex::new(r"Time: (?<time>.*)$").unwrap());
-static RE_DIST: Lazy<Regex> = Lazy::new(|| Regex::new(r"Distance: (?<dist>.*)$").unwrap());
-static RE_NUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+use crate::{
+        charge::{self, Charge},
+        race,
+        sheet::{GameStats, RaceSheet},
+};
 
 // #[tracing::instrument]
 /// Return all integer pairs less oblong than those of 'record' labelled by "Distance".
@@ -28,7 +25,7 @@ static RE_NUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
 /// - We coudl work with floating points and do calculus.
 /// - We can brute force explore integer pairs until we beat it.
 ///   - Biggest input number is 94, and max search is less than half of that. (if we assume all are
-///   winnable)
+///     winnable)
 /// - We can do some algebra and just solve a quadratic equation. (e.g. -9 + 7x x^2 == 0)
 ///  - ( -dist + time *x - x^2 )
 ///
@@ -36,94 +33,30 @@ static RE_NUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
 /// Or we could just look up the quatdratic solution formula -- lol :
 ///   - `x = (-b +/- sqrt(b^2 - 4ac)) / 2a`
 ///
+/// (The quadratic won: `race::winning_holds` solves it exactly, in integers.)
 pub fn process(input: &str) -> Result<usize> {
-        let stats: Vec<GameStats> = input_to_games(input)?;
-
-        Ok(stats.iter()
-                .map(|s| {
-                        let lb = lower_bound_solution(s.max_time, s.record_dist);
-                        lb_to_count(s.max_time, lb)
-                })
-                .inspect(|c| info!("Count: {}", c))
-                .product())
-}
-
-/// Hard coding input.
-fn input_to_games(inp: &str) -> Result<Vec<GameStats>> {
-        let mut lines = inp.lines();
-        let line = lines.next()
-                        .expect("missing line 1");
-        let Some(_) = RE_TIME.captures(line)
-        else {
-                Err(AocErrorDay06::ParseError("Missing Time Line".to_string()))?
-        };
-        let times: Vec<_> = RE_NUM.find_iter(line)
-                                  .map(|m| {
-                                          m.as_str()
-                                           .parse::<i64>()
-                                           .expect("parse failure")
-                                  })
-                                  .collect();
-
-        let line = lines.next()
-                        .expect("missing line 2");
-        let Some(_) = RE_DIST.captures(line)
-        else {
-                Err(AocErrorDay06::ParseError("Missing Dist. Line".to_string()))?
-        };
-        let dists: Vec<_> = RE_NUM.find_iter(line)
-                                  .map(|m| {
-                                          m.as_str()
-                                           .parse::<i64>()
-                                           .expect("parse failure")
-                                  })
-                                  .collect();
-        Ok(times.into_iter()
-                .zip(dists)
-                .map(|(t, d)| GameStats::new(t as u64, d as u64))
-                .collect())
-}
+        let stats: Vec<GameStats> = RaceSheet::parse(input)?.games()?;
 
-/// Game's allowed time and best record distance.
-#[derive(Debug, Constructor)]
-struct GameStats {
-        max_time:    u64,
-        record_dist: u64,
-}
-
-/// Takes lower_bound and max_time and returns the inner count of whole integers
-fn lb_to_count(max_time: u64, lb: u64) -> usize {
-        (lb..=(max_time - lb)).count()
-}
-
-///   Smallest solution to get the record boat score.
-///   (Lower bound of winning numbers, with a mirroring upper bound)
-///   
-///   `x = (-b +/- sqrt(b^2 - 4ac)) / 2a`
-///   `a x^2 + b x + c = 0`
-fn lower_bound_solution(max_time: u64, record_dist: u64) -> u64 {
-        let c_0 = -1.0 * record_dist as f64;
-        let b_1 = max_time as f64;
-        const A_2: f64 = -1.0;
-        // problem requires two (pos) solutions to this to be winnable.
-        // we can use the fact that that correspond to a discriminant > 0 to create a guard.
-        if c_0 * A_2 >= b_1.powi(2) {
-                if c_0 * A_2 == b_1.powi(2) {
-                        panic!("Perfect, unbeatable score!")
-                }
-                panic!("Nonsense, impossible score!");
+        let mut product = 1;
+        for s in &stats {
+                let count = race::ways_to_win(s.max_time.into(), s.record_dist.into())?;
+                info!("Count: {}", count);
+                product *= usize::try_from(count)?;
         }
+        Ok(product)
+}
 
-        let discriminant = b_1.powi(2) - 4.0 * A_2 * c_0;
-        let x_min = (-b_1 + discriminant.sqrt()) / (2.0 * A_2);
+/// `process`, for a race variant: holding the button follows `rule`.
+pub fn process_with(input: &str, rule: &dyn Charge) -> Result<usize> {
+        let stats: Vec<GameStats> = RaceSheet::parse(input)?.games()?;
 
-        let ceil = x_min.ceil() as u64;
-        trace!("{} -> {}", record_dist, (max_time - ceil) * ceil);
-        if ((max_time - ceil) * ceil) == record_dist {
-                debug!("{} -> {}", record_dist, (max_time - ceil) * ceil);
-                return ceil + 1;
+        let mut product = 1;
+        for s in &stats {
+                let count = charge::ways_to_win(rule, s.max_time.into(), s.record_dist.into())?;
+                info!("Count: {}", count);
+                product *= usize::try_from(count)?;
         }
-        ceil
+        Ok(product)
 }
 
 #[cfg(test)]
@@ -131,10 +64,11 @@ mod tests {
         use indoc::indoc;
 
         use super::*;
+        use crate::charge::Polynomial;
 
         #[test]
         fn test_process_example() -> Result<()> {
-                tracing_subscriber::fmt::init();
+                let _ = tracing_subscriber::fmt::try_init();
 
                 let input = indoc! {"
                         Time:      7  15   30
@@ -142,6 +76,7 @@ mod tests {
                 "};
                 let expected = 288;
                 assert_eq!(process(input)?, expected);
+                assert_eq!(process_with(input, &Polynomial::stock())?, expected);
                 Ok(())
         }
 
@@ -155,6 +90,7 @@ mod tests {
                 let file_input = include_str!("../input1.txt");
                 let expected = 345015;
                 assert_eq!(process(file_input)?, expected);
+                assert_eq!(process_with(file_input, &Polynomial::stock())?, expected);
                 Ok(())
         }
 }
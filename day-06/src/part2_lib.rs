@@ -2,16 +2,14 @@
 //! `bin > part2.rs` will run this code along with conent of `input2.txt`
 
 use anyhow::Result;
-use derive_more::Constructor;
-use once_cell::sync::Lazy;
-use regex::Regex;
-use tracing::{debug, trace};
+use num_bigint::BigUint;
+use tracing::debug;
 
-use crate::custom_error::AocErrorDay06;
-
-static RE_TIME: Lazy<Regex> = Lazy::new(|| Regex::new(r"Time: (?<time>.*)$").unwrap());
-static RE_DIST: Lazy<Regex> = Lazy::new(|| Regex::new(r"Distance: (?<dist>.*)$").unwrap());
-static RE_NUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+use crate::{
+        charge::{self, Charge},
+        race,
+        sheet::{GameStats, RaceSheet},
+};
 
 // #[tracing::instrument]
 /// Return all integer pairs less oblong than those of 'record' labelled by "Distance".
@@ -26,7 +24,7 @@ static RE_NUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
 /// - We coudl work with floating points and do calculus.
 /// - We can brute force explore integer pairs until we beat it.
 ///   - Biggest input number is 94, and max search is less than half of that. (if we assume all are
-///   winnable)
+///     winnable)
 /// - We can do some algebra and just solve a quadratic equation. (e.g. -9 + 7x x^2 == 0)
 ///  - ( -dist + time *x - x^2 )
 ///
@@ -34,82 +32,41 @@ static RE_NUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
 /// Or we could just look up the quatdratic solution formula -- lol :
 ///   - `x = (-b +/- sqrt(b^2 - 4ac)) / 2a`
 ///
+/// (The quadratic won: `race::winning_holds` solves it exactly, in integers.)
+///
+/// Numbers too long for a `u64` take the `BigUint` path (`process_arbitrary`).
 pub fn process(input: &str) -> Result<usize> {
-        let stat: GameStats = input_to_game(input)?;
-
-        let lb = lower_bound_solution(stat.max_time, stat.record_dist);
-        Ok(lb_to_count(stat.max_time, lb))
-}
-
-/// joining and parsing
-fn input_to_game(inp: &str) -> Result<GameStats> {
-        let mut lines = inp.lines();
-        let line = lines.next()
-                        .expect("missing line 1");
-        let Some(_) = RE_TIME.captures(line)
-        else {
-                Err(AocErrorDay06::ParseError("Missing Time Line".to_string()))?
-        };
-
-        let nums: Vec<_> = RE_NUM.find_iter(line)
-                                 .map(|m| m.as_str())
-                                 .collect();
-        let time = nums.join("").parse::<u64>()?;
-
-        let line = lines.next()
-                        .expect("missing line 2");
-        let Some(_) = RE_DIST.captures(line)
+        let (time, dist) = RaceSheet::parse(input)?.kerned();
+        let (Ok(max_time), Ok(record_dist)) = (time.parse::<u64>(), dist.parse::<u64>())
         else {
-                Err(AocErrorDay06::ParseError("Missing Dist. Line".to_string()))?
+                debug!("Race too long for u64: {} digits", time.len());
+                return Ok(usize::try_from(ways_to_win_big(&time, &dist)?)?);
         };
-        let nums: Vec<_> = RE_NUM.find_iter(line)
-                                 .map(|m| m.as_str())
-                                 .collect();
-        let dist = nums.join("").parse::<u64>()?;
 
-        Ok(GameStats::new(time, dist))
+        let count = race::ways_to_win(max_time.into(), record_dist.into())?;
+        debug!("Count: {}", count);
+        Ok(usize::try_from(count)?)
 }
 
-/// Game's allowed time and best record distance.
-#[derive(Debug, Constructor)]
-struct GameStats {
-        max_time:    u64,
-        record_dist: u64,
+/// `process`, for races of any length, counted in a `BigUint`.
+pub fn process_arbitrary(input: &str) -> Result<BigUint> {
+        let (time, dist) = RaceSheet::parse(input)?.kerned();
+        ways_to_win_big(&time, &dist)
 }
 
-/// Takes lower_bound and max_time and returns the inner count of whole integers
-fn lb_to_count(max_time: u64, lb: u64) -> usize {
-        (lb..=(max_time - lb)).count()
+fn ways_to_win_big(time: &str, dist: &str) -> Result<BigUint> {
+        let count = race::ways_to_win_big(&time.parse()?, &dist.parse()?)?;
+        debug!("Count: {}", count);
+        Ok(count)
 }
 
-///   Smallest solution to get the record boat score.
-///   (Lower bound of winning numbers, with a mirroring upper bound)
-///   
-///   `x = (-b +/- sqrt(b^2 - 4ac)) / 2a`
-///   `a x^2 + b x + c = 0`
-fn lower_bound_solution(max_time: u64, record_dist: u64) -> u64 {
-        let c_0 = -1.0 * record_dist as f64;
-        let b_1 = max_time as f64;
-        const A_2: f64 = -1.0;
-        // problem requires two (pos) solutions to this to be winnable.
-        // we can use the fact that that correspond to a discriminant > 0 to create a guard.
-        if c_0 * A_2 >= b_1.powi(2) {
-                if c_0 * A_2 == b_1.powi(2) {
-                        panic!("Perfect, unbeatable score!")
-                }
-                panic!("Nonsense, impossible score!");
-        }
-
-        let discriminant = b_1.powi(2) - 4.0 * A_2 * c_0;
-        let x_min = (-b_1 + discriminant.sqrt()) / (2.0 * A_2);
+/// `process`, for a race variant: holding the button follows `rule`.
+pub fn process_with(input: &str, rule: &dyn Charge) -> Result<usize> {
+        let stat: GameStats = RaceSheet::parse(input)?.kerned_game()?;
 
-        let ceil = x_min.ceil() as u64;
-        trace!("{} -> {}", record_dist, (max_time - ceil) * ceil);
-        if ((max_time - ceil) * ceil) == record_dist {
-                debug!("{} -> {}", record_dist, (max_time - ceil) * ceil);
-                return ceil + 1;
-        }
-        ceil
+        let count = charge::ways_to_win(rule, stat.max_time.into(), stat.record_dist.into())?;
+        debug!("Count: {}", count);
+        Ok(usize::try_from(count)?)
 }
 
 #[cfg(test)]
@@ -117,10 +74,11 @@ mod tests {
         use indoc::indoc;
 
         use super::*;
+        use crate::charge::Polynomial;
 
         #[test]
         fn test_process_example() -> Result<()> {
-                tracing_subscriber::fmt::init();
+                let _ = tracing_subscriber::fmt::try_init();
 
                 let input = indoc! {"
                         Time:      7  15   30
@@ -128,9 +86,34 @@ mod tests {
                 "};
                 let expected = 71503;
                 assert_eq!(process(input)?, expected);
+                assert_eq!(process_with(input, &Polynomial::stock())?, expected);
+                assert_eq!(process_arbitrary(input)?, BigUint::from(71503u32));
+                Ok(())
+        }
+
+        #[test]
+        fn test_process_past_u64() -> Result<()> {
+                let _ = tracing_subscriber::fmt::try_init();
+
+                // 10²⁰ ms, record 10⁶ under the best: 1999 holds around the middle
+                let input = indoc! {"
+                        Time:      10000000000 0000000000
+                        Distance:  24999999999999999999 99999999999999000000
+                "};
+                assert_eq!(process(input)?, 1999);
+                assert_eq!(process_arbitrary(input)?, BigUint::from(1999u32));
+                assert!(process_with(input, &Polynomial::stock()).is_err());
                 Ok(())
         }
 
+        #[test]
+        fn test_bad_input() {
+                for input in ["", "Time: 7\n", "Time: 7\nSpeed: 9", "Time: 7\nDistance:"] {
+                        assert!(process(input).is_err(), "{:?}", input);
+                        assert!(process_arbitrary(input).is_err(), "{:?}", input);
+                }
+        }
+
         /// This test's expected value is to be populated after
         /// verification of solution.
         /// (useful for future refactors and perfs)
@@ -141,6 +124,8 @@ mod tests {
                 let file_input = include_str!("../input2.txt");
                 let expected = 42588603;
                 assert_eq!(process(file_input)?, expected);
+                assert_eq!(process_with(file_input, &Polynomial::stock())?, expected);
+                assert_eq!(process_arbitrary(file_input)?, BigUint::from(42588603u32));
                 Ok(())
         }
 }
@@ -0,0 +1,222 @@
+//! Exact winning windows for boat races, shared by both parts.
+//!
+//! Holding the button for `x` ms of a `t` ms race goes `x * (t - x)` mm.
+//! That beats a record `d` for `x` strictly between the roots of `x² - t·x + d = 0`,
+//! i.e. `(t ± √(t² - 4d)) / 2`, and the window is symmetric: `x` wins iff `t - x` does.
+//!
+//! ## Exactness
+//! Everything is integer. `isqrt` gives the floor of the root, which lands within a step of the
+//! first winning hold; checking `x * (t - x) > d` directly then settles the boundary.
+//! (An `f64` root loses whole units once `t² - 4d` passes 2^53.)
+//!
+//! When `t²` doesn't fit in a `u128`, the first winning hold is found by bisection instead,
+//! with checked products: a distance too big for `u128` beats any record.
+//!
+//! Races past even that go through the `BigUint` versions (`*_big`): same root, same settling,
+//! just slower.
+
+use std::ops::RangeInclusive;
+
+use num_bigint::BigUint;
+use tracing::trace;
+
+use crate::custom_error::AocErrorDay06;
+
+/// Distance covered holding for `hold` ms, or `None` if it overflows (or `hold > time`).
+pub fn distance(time: u128, hold: u128) -> Option<u128> {
+        hold.checked_mul(time.checked_sub(hold)?)
+}
+
+/// Whether holding for `hold` ms beats `record`.
+pub fn beats(time: u128, record: u128, hold: u128) -> bool {
+        hold <= time && distance(time, hold).is_none_or(|d| d > record)
+}
+
+/// Every hold that beats the record.
+pub fn winning_holds(time: u128, record: u128) -> Result<RangeInclusive<u128>, AocErrorDay06> {
+        let first = match time.checked_mul(time) {
+                            Some(square) => first_win_exact(time, record, square),
+                            None => first_win_search(time, record),
+                    }.ok_or(AocErrorDay06::Unwinnable { time, record })?;
+        trace!(time, record, first, "winning window");
+        Ok(first..=time - first)
+}
+
+/// How many holds beat the record.
+pub fn ways_to_win(time: u128, record: u128) -> Result<u128, AocErrorDay06> {
+        let holds = winning_holds(time, record)?;
+        Ok(holds.end() - holds.start() + 1)
+}
+
+/// `winning_holds`, for races of any size.
+pub fn winning_holds_big(time: &BigUint,
+                         record: &BigUint)
+                         -> Result<RangeInclusive<BigUint>, AocErrorDay06> {
+        let unwinnable = || AocErrorDay06::UnwinnableBig { time:   time.clone(),
+                                                           record: record.clone(), };
+        let square = time * time;
+        let four_d = record * 4u32;
+        if four_d >= square {
+                return Err(unwinnable());
+        }
+        let mut hold = (time - (square - four_d).sqrt()) / 2u32;
+        // as in `first_win_exact`: a step or so from the boundary
+        let half = time / 2u32;
+        while !beats_big(time, record, &hold) {
+                if hold >= half {
+                        return Err(unwinnable());
+                }
+                hold += 1u32;
+        }
+        while hold > BigUint::ZERO && beats_big(time, record, &(&hold - 1u32)) {
+                hold -= 1u32;
+        }
+        trace!(%time, %record, %hold, "winning window");
+        Ok(hold.clone()..=time - hold)
+}
+
+/// `ways_to_win`, for races of any size.
+pub fn ways_to_win_big(time: &BigUint, record: &BigUint) -> Result<BigUint, AocErrorDay06> {
+        let holds = winning_holds_big(time, record)?;
+        Ok(holds.end() - holds.start() + 1u32)
+}
+
+/// `beats`, for races of any size.
+fn beats_big(time: &BigUint, record: &BigUint, hold: &BigUint) -> bool {
+        hold <= time && hold * (time - hold) > *record
+}
+
+/// First winning hold, from the integer root of the discriminant.
+fn first_win_exact(time: u128, record: u128, square: u128) -> Option<u128> {
+        // `4d > t²` (or too big to say) means even the middle hold can't win
+        let discriminant = square.checked_sub(record.checked_mul(4)?)?;
+        let mut hold = (time - discriminant.isqrt()) / 2;
+        // the floored root may leave `hold` on the boundary, or one short
+        while !beats(time, record, hold) {
+                if hold >= time / 2 {
+                        return None;
+                }
+                hold += 1;
+        }
+        while hold > 0 && beats(time, record, hold - 1) {
+                hold -= 1;
+        }
+        Some(hold)
+}
+
+/// First winning hold, by bisection over the rising half of the race.
+fn first_win_search(time: u128, record: u128) -> Option<u128> {
+        let (mut lo, mut hi) = (0, time / 2);
+        if !beats(time, record, hi) {
+                return None;
+        }
+        // invariant: `hi` wins; everything below `lo` loses
+        while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if beats(time, record, mid) {
+                        hi = mid;
+                }
+                else {
+                        lo = mid + 1;
+                }
+        }
+        Some(hi)
+}
+
+#[cfg(test)]
+mod tests {
+        use super::*;
+
+        fn brute_force(time: u128, record: u128) -> u128 {
+                (0..=time).filter(|&hold| beats(time, record, hold))
+                          .count() as u128
+        }
+
+        #[test]
+        fn test_examples() -> Result<(), AocErrorDay06> {
+                assert_eq!(winning_holds(7, 9)?, 2..=5);
+                assert_eq!(ways_to_win(15, 40)?, 8);
+                // 30 ms ties the record at 10 and 20: ties don't win
+                assert_eq!(winning_holds(30, 200)?, 11..=19);
+                assert_eq!(ways_to_win(71530, 940200)?, 71503);
+                Ok(())
+        }
+
+        #[test]
+        fn test_against_brute_force() {
+                for time in 0..120u128 {
+                        // every record up to a little past the best possible
+                        for record in 0..=time * time / 4 + 2 {
+                                let expected = brute_force(time, record);
+                                match ways_to_win(time, record) {
+                                        Ok(ways) => assert_eq!(ways, expected,
+                                                               "race {} {}",
+                                                               time, record),
+                                        Err(AocErrorDay06::Unwinnable { .. }) => {
+                                                assert_eq!(expected, 0, "race {} {}", time, record)
+                                        },
+                                        Err(e) => panic!("race {} {}: {}", time, record, e),
+                                }
+                        }
+                }
+        }
+
+        #[test]
+        fn test_large_races() -> Result<(), AocErrorDay06> {
+                // past f64's 2^53, the exact and bisection paths must agree and sit on the boundary
+                for (time, record) in [(1u128 << 40, (1u128 << 78) - 12_345),
+                                       (123_456_789_012_345, 3_810_394_688_309_667_280_140_598_756),
+                                       ((1u128 << 63) + 7, 1u128 << 100)]
+                {
+                        let first = *winning_holds(time, record)?.start();
+                        assert_eq!(first_win_search(time, record), Some(first));
+                        assert!(beats(time, record, first) && !beats(time, record, first - 1));
+                }
+                // `t²` overflows: bisection only
+                let time = u128::MAX / 3;
+                let holds = winning_holds(time, u128::MAX - 1)?;
+                assert!(beats(time, u128::MAX - 1, *holds.start()));
+                assert!(!beats(time, u128::MAX - 1, holds.start() - 1));
+
+                assert!(matches!(winning_holds(10, 25), Err(AocErrorDay06::Unwinnable { .. })));
+                assert!(matches!(winning_holds(3, 2), Err(AocErrorDay06::Unwinnable { .. })));
+                Ok(())
+        }
+
+        #[test]
+        fn test_big_matches_u128() -> Result<(), AocErrorDay06> {
+                for time in 0..60u128 {
+                        for record in 0..=time * time / 4 + 2 {
+                                let big = ways_to_win_big(&time.into(), &record.into()).ok();
+                                assert_eq!(big,
+                                           ways_to_win(time, record).ok()
+                                                                    .map(BigUint::from),
+                                           "race {} {}",
+                                           time,
+                                           record);
+                        }
+                }
+                let (time, record) =
+                        (123_456_789_012_345u128, 3_810_394_688_309_667_280_140_598_756);
+                let holds = winning_holds(time, record)?;
+                assert_eq!(winning_holds_big(&time.into(), &record.into())?,
+                           BigUint::from(*holds.start())..=BigUint::from(*holds.end()));
+                Ok(())
+        }
+
+        #[test]
+        fn test_big_races() -> Result<(), AocErrorDay06> {
+                // 60-digit race, record 10⁶ under the best: 1999 holds around the middle
+                let time = BigUint::from(10u32).pow(60);
+                let record = &time * &time / 4u32 - 1_000_000u32;
+                let holds = winning_holds_big(&time, &record)?;
+                assert_eq!(holds.end() - holds.start() + 1u32, BigUint::from(1999u32));
+                assert!(beats_big(&time, &record, holds.start()));
+                assert!(!beats_big(&time, &record, &(holds.start() - 1u32)));
+
+                let best = &time * &time / 4u32;
+                assert!(matches!(ways_to_win_big(&time, &best),
+                                 Err(AocErrorDay06::UnwinnableBig { .. })));
+                Ok(())
+        }
+}
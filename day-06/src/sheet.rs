@@ -0,0 +1,267 @@
+//! The race sheet: a `Time:` record and a `Distance:` record, shared by both parts.
+//!
+//! Reading is table-driven: each line's header is looked up in `RECORDS`, which says which record
+//! it fills. So records come in any order, and blank lines, stray whitespace and CRLF are fine.
+//! Anything else (an unknown header, a repeat, a record missing or without numbers) is an
+//! `InvalidSheet` error, labelled in the input.
+//!
+//! ## e.g.
+//! ```text
+//! Time:      7  15   30      games():  7 ms / 9 mm,  15 ms / 40 mm,  30 ms / 200 mm
+//! Distance:  9  40  200      kerned(): 71530 ms / 940200 mm
+//! ```
+
+use std::ops::Range;
+
+use aoc_common::patterns;
+use derive_more::Constructor;
+use miette::SourceSpan;
+
+use crate::custom_error::{AocErrorDay06, InvalidSheet, SheetProblem};
+
+patterns! { RE_NUM = r"\d+"; }
+
+/// Game's allowed time and best record distance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Constructor)]
+pub struct GameStats {
+        pub max_time:    u64,
+        pub record_dist: u64,
+}
+
+/// The records on a sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Record {
+        Time,
+        Distance,
+}
+
+/// Headers, and which record each one fills.
+const RECORDS: [(&str, Record); 2] = [("Time", Record::Time), ("Distance", Record::Distance)];
+
+impl Record {
+        pub fn header(self) -> &'static str {
+                RECORDS.iter()
+                       .find(|(_, r)| *r == self)
+                       .map_or("?", |(header, _)| header)
+        }
+}
+
+/// One record's line: where it is, and its numbers (each with its offset in the input).
+#[derive(Debug, Clone)]
+struct Row<'a> {
+        line:    Range<usize>,
+        numbers: Vec<(usize, &'a str)>,
+}
+
+impl Row<'_> {
+        /// From the first number to the end of the last.
+        fn numbers_span(&self) -> SourceSpan {
+                let (start, _) = self.numbers[0];
+                let (last, digits) = self.numbers[self.numbers.len() - 1];
+                (start, last + digits.len() - start).into()
+        }
+}
+
+/// A sheet with both records, numbers still as written.
+#[derive(Debug, Clone)]
+pub struct RaceSheet<'a> {
+        src:  &'a str,
+        /// In `RECORDS` order.
+        rows: [Row<'a>; 2],
+}
+
+impl<'a> RaceSheet<'a> {
+        pub fn parse(src: &'a str) -> Result<Self, AocErrorDay06> {
+                let mut rows: [Option<Row>; 2] = [None, None];
+                let mut offset = 0;
+                for raw in src.split_inclusive('\n') {
+                        let start = offset;
+                        offset += raw.len();
+                        let text = raw.trim_end();
+                        let at = start + text.len() - text.trim_start().len();
+                        let text = text.trim_start();
+                        if text.is_empty() {
+                                continue;
+                        }
+
+                        let (header, rest) = text.split_once(':')
+                                                 .unwrap_or((text, ""));
+                        let Some(&(name, record)) =
+                                RECORDS.iter()
+                                       .find(|(name, _)| *name == header.trim_end())
+                        else {
+                                let problem = SheetProblem::UnknownHeader(header.to_string());
+                                return Err(invalid(src, problem, at..at + header.len()).into());
+                        };
+                        let line = at..at + text.len();
+                        let slot = &mut rows[record as usize];
+                        if let Some(first) = slot {
+                                let first: SourceSpan = first.line.clone().into();
+                                let err = invalid(src, SheetProblem::Duplicate(name), line);
+                                return Err(err.also(src, first, "first one")
+                                              .into());
+                        }
+
+                        let rest_at = at + header.len() + 1;
+                        let numbers: Vec<_> = RE_NUM.find_iter(rest)
+                                                    .map(|m| (rest_at + m.start(), m.as_str()))
+                                                    .collect();
+                        if numbers.is_empty() {
+                                return Err(invalid(src, SheetProblem::NoNumbers(name), line).into());
+                        }
+                        *slot = Some(Row { line, numbers });
+                }
+
+                match rows {
+                        [Some(time), Some(distance)] => Ok(Self { src,
+                                                                  rows: [time, distance] }),
+                        _ => {
+                                let missing = rows.iter()
+                                                  .position(Option::is_none)
+                                                  .unwrap_or(0);
+                                let problem = SheetProblem::Missing(RECORDS[missing].0);
+                                // nowhere to point but the end
+                                Err(invalid(src, problem, (src.len(), 0)).into())
+                        },
+                }
+        }
+
+        /// One game per column: the part 1 reading.
+        pub fn games(&self) -> Result<Vec<GameStats>, AocErrorDay06> {
+                let [time, distance] = &self.rows;
+                if time.numbers.len() != distance.numbers.len() {
+                        let problem = SheetProblem::ColumnMismatch { times: time.numbers.len(),
+                                                                     dists: distance.numbers.len(), };
+                        let err = invalid(self.src, problem, time.numbers_span());
+                        return Err(err.also(self.src, distance.numbers_span(), "distances")
+                                      .into());
+                }
+                time.numbers
+                    .iter()
+                    .zip(&distance.numbers)
+                    .map(|(&(t_at, t), &(d_at, d))| {
+                            Ok(GameStats::new(self.u64(Record::Time,
+                                                       t,
+                                                       t_at..t_at + t.len())?,
+                                              self.u64(Record::Distance,
+                                                       d,
+                                                       d_at..d_at + d.len())?))
+                    })
+                    .collect()
+        }
+
+        /// Each record's digits run together, any length: the part 2 reading.
+        pub fn kerned(&self) -> (String, String) {
+                let [time, distance] = self.rows
+                                           .each_ref()
+                                           .map(|row| {
+                                                   row.numbers
+                                                      .iter()
+                                                      .map(|(_, digits)| *digits)
+                                                      .collect()
+                                           });
+                (time, distance)
+        }
+
+        /// `kerned`, as one game.
+        pub fn kerned_game(&self) -> Result<GameStats, AocErrorDay06> {
+                let (time, distance) = self.kerned();
+                let [time_span, distance_span] = self.rows
+                                                     .each_ref()
+                                                     .map(Row::numbers_span);
+                Ok(GameStats::new(self.u64(Record::Time, &time, time_span)?,
+                                  self.u64(Record::Distance,
+                                           &distance,
+                                           distance_span)?))
+        }
+
+        /// `digits` as a `u64`, or a `TooBig` error labelling `span`.
+        fn u64(&self,
+               record: Record,
+               digits: &str,
+               span: impl Into<SourceSpan>)
+               -> Result<u64, AocErrorDay06> {
+                Ok(digits.parse().map_err(|_| {
+                                          invalid(self.src,
+                                                  SheetProblem::TooBig(record.header()),
+                                                  span)
+                                  })?)
+        }
+}
+
+fn invalid(src: &str, problem: SheetProblem, span: impl Into<SourceSpan>) -> InvalidSheet {
+        InvalidSheet::new("race sheet", src, problem, span)
+}
+
+#[cfg(test)]
+mod tests {
+        use indoc::indoc;
+
+        use super::*;
+
+        const EXAMPLE: &str = indoc! {"
+                Time:      7  15   30
+                Distance:  9  40  200
+        "};
+
+        fn problem(input: &str) -> (SheetProblem, SourceSpan, Option<SourceSpan>) {
+                let err = RaceSheet::parse(input).and_then(|sheet| sheet.games())
+                                                 .expect_err(input);
+                let AocErrorDay06::InvalidSheet(err) = err
+                else {
+                        panic!("expected an invalid sheet, got {:?}", err);
+                };
+                (err.problem, err.span, err.other)
+        }
+
+        #[test]
+        fn test_readings() -> Result<(), AocErrorDay06> {
+                let sheet = RaceSheet::parse(EXAMPLE)?;
+                assert_eq!(sheet.games()?, [GameStats::new(7, 9),
+                                            GameStats::new(15, 40),
+                                            GameStats::new(30, 200)]);
+                assert_eq!(sheet.kerned(), ("71530".to_string(), "940200".to_string()));
+                assert_eq!(sheet.kerned_game()?, GameStats::new(71530, 940200));
+                Ok(())
+        }
+
+        #[test]
+        fn test_tolerates_layout() -> Result<(), AocErrorDay06> {
+                let expected = RaceSheet::parse(EXAMPLE)?.games()?;
+                for input in ["Distance:  9  40  200\nTime:      7  15   30\n",
+                              "\n\n  Time:   7 15 30  \n\n\tDistance :9 40 200\n\n",
+                              "Time:      7  15   30\r\nDistance:  9  40  200\r\n",
+                              "Time: 7 15 30\nDistance: 9 40 200"]
+                {
+                        assert_eq!(RaceSheet::parse(input)?.games()?, expected, "{:?}", input);
+                }
+                Ok(())
+        }
+
+        #[test]
+        fn test_errors() {
+                // spans are (offset, length) in the whole input
+                let span = |offset: usize, len: usize| SourceSpan::from((offset, len));
+
+                assert_eq!(problem("Time: 7 15\nDistance: 9 40 200\n"),
+                           (SheetProblem::ColumnMismatch { times: 2, dists: 3 },
+                            span(6, 4),
+                            Some(span(21, 8))));
+                assert_eq!(problem("Time: 7\nTime: 8\nDistance: 9\n"),
+                           (SheetProblem::Duplicate("Time"), span(8, 7), Some(span(0, 7))));
+                assert_eq!(problem("Time: 7\n  Speed: 9\n"),
+                           (SheetProblem::UnknownHeader("Speed".to_string()), span(10, 5), None));
+                assert_eq!(problem("Time: 7\nDistance:\n"),
+                           (SheetProblem::NoNumbers("Distance"), span(8, 9), None));
+                assert_eq!(problem("Time: 7\n"),
+                           (SheetProblem::Missing("Distance"), span(8, 0), None));
+                assert_eq!(problem(""),
+                           (SheetProblem::Missing("Time"), span(0, 0), None));
+                assert_eq!(problem("Time: 7 99999999999999999999\nDistance: 9 1\n"),
+                           (SheetProblem::TooBig("Time"), span(8, 20), None));
+
+                // kerning turns a column mismatch into one long race
+                let sheet = RaceSheet::parse("Time: 7 15\nDistance: 9 40 200\n").unwrap();
+                assert_eq!(sheet.kerned_game().unwrap(), GameStats::new(715, 940200));
+        }
+}
@@ -1,5 +1,8 @@
 //! Custom Error type for Day 07 of Advent of Code 2023.
 
+use std::fmt;
+
+use aoc_common::spanned::InvalidInput;
 use miette::Diagnostic;
 use thiserror::Error;
 
@@ -8,4 +11,52 @@ pub enum AocErrorDay07 {
         #[error(transparent)]
         #[diagnostic(code(aoc::io_error))]
         IoError(#[from] std::io::Error),
+
+        #[error("Invalid ruleset: {0}")]
+        #[diagnostic(code(aoc::invalid_ruleset))]
+        InvalidRuleset(String),
+
+        #[error(transparent)]
+        #[diagnostic(transparent)]
+        InvalidHand(Box<InvalidHand>),
+}
+
+/// A hand line the ruleset can't read.
+pub type InvalidHand = InvalidInput<HandProblem>;
+
+impl From<InvalidHand> for AocErrorDay07 {
+        fn from(err: InvalidHand) -> Self {
+                Self::InvalidHand(Box::new(err))
+        }
+}
+
+/// What's wrong with an `InvalidHand`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandProblem {
+        /// Not a label in the ruleset's card order.
+        UnknownCard(char),
+        /// Wrong number of cards for the ruleset.
+        Size {
+                expected: usize,
+                found:    usize,
+        },
+        MissingBid,
+        /// Something after the bid.
+        Extra,
+        /// Bid isn't a `u64`.
+        Bid(String),
+}
+
+impl fmt::Display for HandProblem {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                        Self::UnknownCard(c) => write!(f, "unknown card {:?}", c),
+                        Self::Size { expected, found } => {
+                                write!(f, "{} cards, expected {}", found, expected)
+                        },
+                        Self::MissingBid => write!(f, "no bid after the cards"),
+                        Self::Extra => write!(f, "expected `<cards> <bid>` only"),
+                        Self::Bid(bid) => write!(f, "bid {:?} isn't a whole number", bid),
+                }
+        }
 }
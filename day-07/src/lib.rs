@@ -4,9 +4,7 @@
 //! (With redundancy common, and by design, between those two files.)
 
 pub mod custom_error;
-
-pub mod lexer_1;
-pub mod lexer_2;
+pub mod ruleset;
 
 pub mod part1_lib;
 pub mod part2_lib;
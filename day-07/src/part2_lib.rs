@@ -1,129 +1,17 @@
 //! Library code for Part 2 of Day 07 of Advent of Code 2023.
 //! `bin > part2.rs` will run this code along with conent of `input2.txt`
 
-// use crate::custom_error::AocErrorDay07;
-// use regex::Regex;
-use std::collections::HashMap;
-
 use anyhow::Result;
-use derive_more::Constructor;
-use itertools::Itertools;
-use logos::Logos;
-// use once_cell::sync::Lazy;
-use rayon::prelude::*;
 use tracing::{event, Level};
 
-use crate::lexer_2::{Card, Token};
-// use miette::Result;
-
-/// Hand Types
-/// note: derived order is asscending from top to bottom as written.
-///       (e.g. here, c2 < c3 < c4 < ... < cA)
-#[allow(non_camel_case_types)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum HType {
-        H____1,
-        H___22,
-        H_2222,
-        H__333,
-        H22333,
-        H_4444,
-        H55555,
-}
-impl HType {
-        #[tracing::instrument]
-        fn from_hand(hand: &Hand) -> Option<HType> {
-                // frequency map of cards
-                let mut fmap = HashMap::<Card, u8>::new();
-                for card in hand.cards.iter() {
-                        let count = fmap.entry(*card).or_insert(0);
-                        *count += 1;
-                }
-
-                // remove 'J's
-                let j_count = fmap.remove_entry(&Card::CJ)
-                                  .map(|(_, v)| v);
-                let mut counts: Vec<u8> = fmap.into_values().collect();
-                counts.sort();
-                if let Some(j_count) = j_count {
-                        event!(Level::INFO,
-                               "(called from parallelized routine) enter {:?}",
-                               counts);
-                        let mb_highest = counts.last_mut();
-                        match mb_highest {
-                                Some(highest) => *highest += j_count,
-                                None => counts.push(j_count), // in case there were only Js
-                        }
-                        event!(Level::WARN,
-                               "(called from parallelized routine) exit {:?}",
-                               counts);
-                }
-                match counts.as_slice() {
-                        [_, _, _, _, _] => Some(HType::H____1),
-                        [_, _, _, _] => Some(HType::H___22),
-                        [_, 2, _] => Some(HType::H_2222),
-                        [_, _, 3] => Some(HType::H__333),
-                        [_, 3] => Some(HType::H22333),
-                        [_, 4] => Some(HType::H_4444),
-                        [_] => Some(HType::H55555),
-                        _ => panic!("unhandalable hand: {:?}", hand),
-                }
-        }
-}
-
-/// Hand of specific cars, with htype and a bid.
-/// (ranking not specified, expected to be inferred from context)
-/// Ord is derivable with dictionary ordering from top to bottom as written.
-/// NOTE: this is a bit the opposite of Enums, where lower is higher
-///       here higher is higher.  ...that's quite unfortunate.
-#[derive(Constructor, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Hand {
-        htype: Option<HType>,
-        cards: [Card; 5],
-        bid:   u64,
-}
-impl Hand {
-        /// Sets hand's htype if not already set.
-        fn determine_htype(&mut self) {
-                if self.htype.is_some() {
-                        return;
-                }
-                self.htype = HType::from_hand(self);
-        }
-}
+use crate::ruleset::Ruleset;
 
+/// Total winnings, under the part 2 rules: `J` is a joker (wild, but weakest on ties).
+/// (See `Ruleset::part2`; the engine is shared with part 1.)
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> Result<u64> {
-        event!(Level::INFO, "Hiii. from  day-07 Part1! :)");
-        let mut hands: Vec<Hand> =
-                Token::lexer(input).spanned()
-                                   .filter_map(|(t, _)| t.ok())
-                                   .chunks(2)
-                                   .into_iter()
-                                   .map(|mut chunk| {
-                                           let hand = chunk.next().unwrap();
-                                           let bid = chunk.next().unwrap();
-                                           Hand::new(None,
-                                                     hand.unwrap_proto_hand(),
-                                                     bid.unwrap_bid())
-                                   })
-                                   .inspect(|h| event!(Level::TRACE, "Hand: {:?}", h))
-                                   .collect();
-
-        hands.par_iter_mut()
-             .for_each(|h| {
-                     (*h).determine_htype();
-                     event!(Level::TRACE, "Hand: {:?}", h);
-             });
-        event!(Level::TRACE, "Hands: {:?}", hands);
-        // using stable sort just in case...
-        hands.sort();
-        event!(Level::DEBUG, "Hands: {:?}", hands);
-
-        Ok(hands.par_iter()
-                .enumerate()
-                .map(|(id, h)| h.bid * (id as u64 + 1))
-                .sum())
+        event!(Level::INFO, "Hiii. from  day-07 Part2! :)");
+        Ok(Ruleset::part2().winnings(input)?)
 }
 
 #[cfg(test)]
@@ -134,7 +22,7 @@ mod tests {
 
         #[test]
         fn test_process_example() -> Result<()> {
-                tracing_subscriber::fmt::init();
+                let _ = tracing_subscriber::fmt::try_init();
 
                 let input = indoc! {"
                         32T3K 765
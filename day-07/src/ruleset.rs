@@ -0,0 +1,327 @@
+//! Camel Cards, with the rules as data.
+//!
+//! The two parts only differ in card order and in `J` being wild. A `Ruleset` spells out
+//! everything a variant can change, and one engine scores any of them:
+//! - card order, weakest first (which also settles ties between hands of one type)
+//! - which ranks are wild
+//! - hand size
+//! - the hand-type table, weakest first
+//!
+//! ## Hand types
+//! Each type is the card groups it needs, e.g. `[3, 2]` for a full house.
+//! A hand is the strongest type it can make: its own groups, largest first, must cover the type's,
+//! with wild cards making up any shortfall. (Matching largest to largest wastes the fewest wilds,
+//! so that one check settles it, however many wild ranks there are.)
+//!
+//! ## e.g.
+//! ```text
+//! Ruleset::part1()    23456789TJQKA, no wilds, 5 cards, `standard_types()`
+//! Ruleset::part2()    J23456789TQKA, J wild,   5 cards, `standard_types()`
+//! ```
+
+use std::collections::HashSet;
+
+use derive_more::Constructor;
+use miette::SourceSpan;
+use rayon::prelude::*;
+use tracing::{debug, trace};
+
+use crate::custom_error::{AocErrorDay07, HandProblem, InvalidHand};
+
+/// A hand type: its name, and the card groups it needs, largest first.
+#[derive(Debug, Clone, PartialEq, Eq, Constructor)]
+pub struct HandType {
+        pub name:   &'static str,
+        pub groups: Vec<usize>,
+}
+
+/// The puzzle's seven hand types, weakest first.
+/// (Also fine for bigger hands: six of a kind still counts as five.)
+pub fn standard_types() -> Vec<HandType> {
+        vec![HandType::new("high card", vec![]),
+             HandType::new("one pair", vec![2]),
+             HandType::new("two pair", vec![2, 2]),
+             HandType::new("three of a kind", vec![3]),
+             HandType::new("full house", vec![3, 2]),
+             HandType::new("four of a kind", vec![4]),
+             HandType::new("five of a kind", vec![5]),]
+}
+
+/// One way to play Camel Cards.
+#[derive(Debug, Clone)]
+pub struct Ruleset {
+        /// Card labels, weakest first.
+        order:     Vec<char>,
+        /// Indexed by rank.
+        wild:      Vec<bool>,
+        hand_size: usize,
+        /// Weakest first.
+        types:     Vec<HandType>,
+}
+
+/// A parsed hand: each card's rank (its index in the ruleset's order), and the bid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hand {
+        pub ranks: Vec<u8>,
+        pub bid:   u64,
+}
+
+impl Ruleset {
+        /// `order` weakest first; `wild` a subset of it; `types` weakest first.
+        pub fn new(order: &str,
+                   wild: &str,
+                   hand_size: usize,
+                   mut types: Vec<HandType>)
+                   -> Result<Self, AocErrorDay07> {
+                let order: Vec<char> = order.chars().collect();
+                let invalid = |why: String| Err(AocErrorDay07::InvalidRuleset(why));
+                if order.len() > usize::from(u8::MAX) {
+                        return invalid(format!("{} card labels, at most 255", order.len()));
+                }
+                if order.iter()
+                        .collect::<HashSet<_>>()
+                        .len()
+                   != order.len()
+                {
+                        return invalid(format!("repeated card label in {:?}", order));
+                }
+                if let Some(c) = wild.chars()
+                                     .find(|c| !order.contains(c))
+                {
+                        return invalid(format!("wild card {:?} isn't in the card order", c));
+                }
+                if hand_size == 0 || types.is_empty() {
+                        return invalid("need a hand size and at least one hand type".to_string());
+                }
+                for t in &mut types {
+                        t.groups
+                         .sort_unstable_by(|a, b| b.cmp(a));
+                        if t.groups.iter().sum::<usize>() > hand_size {
+                                return invalid(format!("`{}` needs more than {} cards",
+                                                       t.name, hand_size));
+                        }
+                }
+                Ok(Self { wild: order.iter()
+                                     .map(|c| wild.contains(*c))
+                                     .collect(),
+                          order,
+                          hand_size,
+                          types })
+        }
+
+        /// Part 1: `J` is a jack.
+        pub fn part1() -> Self {
+                Self::new("23456789TJQKA", "", 5, standard_types()).expect("part 1 rules are valid")
+        }
+
+        /// Part 2: `J` is a joker, wild but weakest on ties.
+        pub fn part2() -> Self {
+                Self::new("J23456789TQKA", "J", 5, standard_types()).expect("part 2 rules are valid")
+        }
+
+        pub fn types(&self) -> &[HandType] {
+                &self.types
+        }
+
+        /// Index into `types` of the strongest type `ranks` can make.
+        pub fn hand_type(&self, ranks: &[u8]) -> usize {
+                let mut counts = vec![0; self.order.len()];
+                for &r in ranks {
+                        counts[usize::from(r)] += 1;
+                }
+                let mut wilds = 0;
+                let mut groups: Vec<usize> = Vec::new();
+                for (rank, &n) in counts.iter().enumerate() {
+                        if self.wild[rank] {
+                                wilds += n;
+                        }
+                        else if n > 0 {
+                                groups.push(n);
+                        }
+                }
+                groups.sort_unstable_by(|a, b| b.cmp(a));
+
+                let shortfall = |needs: &[usize]| -> usize {
+                        needs.iter()
+                             .enumerate()
+                             .map(|(i, &need)| {
+                                     need.saturating_sub(groups.get(i)
+                                                               .copied()
+                                                               .unwrap_or(0))
+                             })
+                             .sum()
+                };
+                self.types
+                    .iter()
+                    .rposition(|t| shortfall(&t.groups) <= wilds)
+                    .unwrap_or(0)
+        }
+
+        /// One `<cards> <bid>` line per hand; blank lines are skipped.
+        pub fn parse_hands(&self, input: &str) -> Result<Vec<Hand>, AocErrorDay07> {
+                let mut hands = Vec::new();
+                let mut offset = 0;
+                for raw in input.split_inclusive('\n') {
+                        let start = offset;
+                        offset += raw.len();
+                        if raw.trim().is_empty() {
+                                continue;
+                        }
+                        hands.push(self.parse_hand(raw.trim_end())
+                                       .map_err(|(problem, span)| {
+                                               InvalidHand::new("hand", raw, problem, span)
+                                                       .within(input, start)
+                                       })?);
+                }
+                Ok(hands)
+        }
+
+        /// A hand line, or what's wrong with it and where (within the line).
+        fn parse_hand(&self, line: &str) -> Result<Hand, (HandProblem, SourceSpan)> {
+                // where `word` (a slice of `line`) starts in it
+                let at = |word: &str| word.as_ptr() as usize - line.as_ptr() as usize;
+                let mut words = line.split_whitespace();
+                let cards = words.next()
+                                 .unwrap_or_default();
+                let cards_at = at(cards);
+                let cards_span = SourceSpan::from((cards_at, cards.len()));
+
+                let mut ranks = Vec::with_capacity(self.hand_size);
+                for (i, c) in cards.char_indices() {
+                        let Some(rank) = self.order
+                                             .iter()
+                                             .position(|&o| o == c)
+                        else {
+                                return Err((HandProblem::UnknownCard(c),
+                                            (cards_at + i, c.len_utf8()).into()));
+                        };
+                        ranks.push(rank as u8);
+                }
+                if ranks.len() != self.hand_size {
+                        return Err((HandProblem::Size { expected: self.hand_size,
+                                                        found:    ranks.len(), },
+                                    cards_span));
+                }
+
+                let bid = match (words.next(), words.next()) {
+                        (Some(bid), None) => bid,
+                        (None, _) => return Err((HandProblem::MissingBid, cards_span)),
+                        (Some(_), Some(_)) =>
+                                return Err((HandProblem::Extra,
+                                            (cards_at, line.len() - cards_at).into())),
+                };
+                let bid =
+                        bid.parse()
+                           .map_err(|_| {
+                                   (HandProblem::Bid(bid.to_string()), (at(bid), bid.len()).into())
+                           })?;
+                Ok(Hand { ranks, bid })
+        }
+
+        /// Total winnings: each hand's bid times its rank, weakest hand first.
+        pub fn winnings(&self, input: &str) -> Result<u64, AocErrorDay07> {
+                let hands = self.parse_hands(input)?;
+                let mut keyed: Vec<(usize, &[u8], u64)> =
+                        hands.par_iter()
+                             .map(|h| (self.hand_type(&h.ranks), h.ranks.as_slice(), h.bid))
+                             .collect();
+                keyed.sort_unstable();
+                trace!(?keyed);
+                debug!(hands = keyed.len(), "ranked");
+
+                Ok(keyed.iter()
+                        .zip(1..)
+                        .map(|(&(_, _, bid), rank)| bid * rank)
+                        .sum())
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use indoc::indoc;
+
+        use super::*;
+
+        const EXAMPLE: &str = indoc! {"
+                32T3K 765
+                T55J5 684
+                KK677 28
+                KTJJT 220
+                QQQJA 483
+        "};
+
+        fn type_of(rules: &Ruleset, cards: &str) -> &'static str {
+                let hand = rules.parse_hand(&format!("{} 1", cards))
+                                .expect(cards);
+                rules.types()[rules.hand_type(&hand.ranks)].name
+        }
+
+        #[test]
+        fn test_presets() -> Result<(), AocErrorDay07> {
+                assert_eq!(Ruleset::part1().winnings(EXAMPLE)?, 6440);
+                assert_eq!(Ruleset::part2().winnings(EXAMPLE)?, 5905);
+
+                let (p1, p2) = (Ruleset::part1(), Ruleset::part2());
+                for (cards, plain, joker) in [("32T3K", "one pair", "one pair"),
+                                              ("T55J5", "three of a kind", "four of a kind"),
+                                              ("KTJJT", "two pair", "four of a kind"),
+                                              ("JJJJJ", "five of a kind", "five of a kind"),
+                                              ("2345J", "high card", "one pair"),
+                                              ("22JJ3", "two pair", "four of a kind"),
+                                              ("23J3J", "two pair", "four of a kind")]
+                {
+                        assert_eq!(type_of(&p1, cards), plain, "{}", cards);
+                        assert_eq!(type_of(&p2, cards), joker, "{}", cards);
+                }
+                Ok(())
+        }
+
+        #[test]
+        fn test_variants() -> Result<(), AocErrorDay07> {
+                // two wild ranks
+                let two_wild = Ruleset::new("JW23456789TQKA", "JW", 5, standard_types())?;
+                assert_eq!(type_of(&two_wild, "JW233"), "four of a kind");
+                assert_eq!(type_of(&two_wild, "2W345"), "one pair");
+
+                // six-card hands, with types of their own
+                let mut types = standard_types();
+                types.insert(5, HandType::new("two triples", vec![3, 3]));
+                types.push(HandType::new("six of a kind", vec![6]));
+                let six = Ruleset::new("J23456789TQKA", "J", 6, types)?;
+                assert_eq!(type_of(&six, "222333"), "two triples");
+                assert_eq!(type_of(&six, "22233J"), "four of a kind");
+                assert_eq!(type_of(&six, "22223J"), "five of a kind");
+                assert_eq!(type_of(&six, "JJJJJJ"), "six of a kind");
+                assert_eq!(six.winnings("222333 10\n222234 1\n23456J 100\n")?,
+                           100 + 20 + 3);
+
+                assert!(Ruleset::new("AA", "", 5, standard_types()).is_err());
+                assert!(Ruleset::new("23", "J", 5, standard_types()).is_err());
+                assert!(Ruleset::new("23", "", 4, standard_types()).is_err());
+                Ok(())
+        }
+
+        #[test]
+        fn test_bad_hands() {
+                let rules = Ruleset::part1();
+                let problem = |input: &str| {
+                        let Err(AocErrorDay07::InvalidHand(err)) = rules.parse_hands(input)
+                        else {
+                                panic!("expected an invalid hand: {:?}", input);
+                        };
+                        (err.line, err.problem, err.span.offset(), err.span.len())
+                };
+                assert_eq!(problem("32T3K 765\nKK6X7 28\n"),
+                           (2, HandProblem::UnknownCard('X'), 13, 1));
+                assert_eq!(problem("32T3 765\n"),
+                           (1,
+                            HandProblem::Size { expected: 5,
+                                                found:    4, },
+                            0,
+                            4));
+                assert_eq!(problem("\n  32T3K\n"), (2, HandProblem::MissingBid, 3, 5));
+                assert_eq!(problem("32T3K x7\n"),
+                           (1, HandProblem::Bid("x7".to_string()), 6, 2));
+                assert_eq!(problem("32T3K 1 2\n"), (1, HandProblem::Extra, 0, 9));
+        }
+}
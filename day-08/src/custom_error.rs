@@ -1,5 +1,6 @@
 //! Custom Error type for Day 08 of Advent of Code 2023.
 
+use aoc_common::patterns::PatternError;
 use derive_more::Display;
 use thiserror::Error;
 
@@ -7,4 +8,6 @@ use thiserror::Error;
 pub enum AocErrorDay08 {
         IoError,
         SolutionsLengthMismatch,
+        /// A line that isn't a graph component.
+        GraphComponent(#[from] PatternError),
 }
@@ -32,14 +32,15 @@
 
 use std::collections::{HashMap, HashSet};
 
+use aoc_common::{bytes_patterns, captures::FromCaptures, patterns::PatternError};
 use derive_more::Constructor;
 use nalgebra::DMatrix;
-use once_cell::sync::Lazy;
 use path_input::*;
-use regex::bytes;
 use tracing::{event, Level};
 
-pub fn process_input(input: &str) -> (Vec<Direction>, DMatrix<u8>, DMatrix<u8>) {
+#[allow(clippy::type_complexity)]
+pub fn process_input(input: &str)
+                     -> Result<(Vec<Direction>, DMatrix<u8>, DMatrix<u8>), PatternError> {
         let mut input_lines = input.lines();
         let first_line = input_lines.next()
                                     .expect("Input should have at least one line");
@@ -47,8 +48,8 @@ pub fn process_input(input: &str) -> (Vec<Direction>, DMatrix<u8>, DMatrix<u8>)
         let component_lines = input_lines.skip(1)
                                          .map(|line| line.as_bytes())
                                          .collect::<Vec<_>>();
-        let (l_mat, r_mat) = graph_components::process_components(component_lines);
-        (directions, l_mat, r_mat)
+        let (l_mat, r_mat) = graph_components::process_components(component_lines)?;
+        Ok((directions, l_mat, r_mat))
 }
 
 pub mod graph_components {
@@ -64,30 +65,24 @@ pub mod graph_components {
                 \)                    # ')'
         "; // note: `(?x)` enables "verbose mode" for regex string, where whitespace is ignored
 
+        bytes_patterns! { RE_GRAPH_COMP = GRAPH_COMPONENT; }
+
         /// Raw Graph Component Data read from a Byte-String
-        #[derive(Constructor, Debug, Clone, Copy, PartialEq, Eq)]
+        #[derive(FromCaptures, Constructor, Debug, Clone, Copy, PartialEq, Eq)]
+        #[captures(pattern = RE_GRAPH_COMP, bytes)]
         struct RawGraphComponent {
+                #[captures(group = "inp")]
                 input:        [u8; 3],
+                #[captures(group = "l_out")]
                 left_output:  [u8; 3],
+                #[captures(group = "r_out")]
                 right_output: [u8; 3],
         }
 
         /// Parse a RawGraphComponent from a Byte-String
         /// Using Regex Capture groups
-        fn parse_raw_graph_component(hay: &[u8]) -> RawGraphComponent {
-                static RE_GRAPH_COMP: Lazy<bytes::Regex> =
-                        Lazy::new(|| bytes::Regex::new(GRAPH_COMPONENT).unwrap());
-
-                let (_, [inp, l_out, r_out]) = RE_GRAPH_COMP.captures(hay)
-                                                            .map(|caps| caps.extract())
-                                                            .expect("Regex Extraction Failure.");
-
-                RawGraphComponent { input:        inp.try_into()
-                                                     .expect("invalid input node"),
-                                    left_output:  l_out.try_into()
-                                                       .expect("invalid input node"),
-                                    right_output: r_out.try_into()
-                                                       .expect("invalid input node"), }
+        fn parse_raw_graph_component(hay: &[u8]) -> Result<RawGraphComponent, PatternError> {
+                RawGraphComponent::try_from(hay)
         }
 
         // TODO:
@@ -99,10 +94,11 @@ pub mod graph_components {
         // NOTE: AAA -> 0 & ZZZ -> nodes.len()-1; so we shouldn't need to search by Node name.
 
         /// Rather messy construction of a couple Graph Matrices.
-        pub fn process_components(input_lines: Vec<&[u8]>) -> (DMatrix<u8>, DMatrix<u8>) {
+        pub fn process_components(input_lines: Vec<&[u8]>)
+                                  -> Result<(DMatrix<u8>, DMatrix<u8>), PatternError> {
                 let components: Vec<RawGraphComponent> = input_lines.into_iter()
                                                                     .map(parse_raw_graph_component)
-                                                                    .collect();
+                                                                    .collect::<Result<_, _>>()?;
 
                 // Collect input and output nodes separately
                 let mut input_nodes = HashSet::new();
@@ -182,7 +178,7 @@ pub mod graph_components {
                         }
                 }
 
-                (left_matrix, right_matrix)
+                Ok((left_matrix, right_matrix))
         }
 
         // PERF: Sparse Matrices
@@ -241,8 +237,7 @@ pub mod path_input {
         /// Take a string with L*R* and return a vector of Directions
         /// (Originally written to take the first line of problem input.)
         pub fn line_to_directions(hay: &[u8]) -> Vec<Direction> {
-                static RE_INPUT: Lazy<bytes::Regex> =
-                        Lazy::new(|| bytes::Regex::new(INPUT).unwrap());
+                bytes_patterns! { RE_INPUT = INPUT; }
                 let (_, [path]) = RE_INPUT.captures(hay)
                                           .map(|caps| caps.extract())
                                           .expect("Regex Extraction Failure.");
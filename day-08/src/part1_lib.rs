@@ -80,7 +80,7 @@ use crate::{
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> Result<usize, AocErrorDay08> {
         event!(Level::INFO, "Hiii. from  day-08 Part1! :)");
-        let (dirs, l_mat, r_mat) = process_input(input);
+        let (dirs, l_mat, r_mat) = process_input(input)?;
         event!(Level::INFO, "dirs: {:?}", dirs);
         event!(Level::TRACE, "l_mat: {}", l_mat);
         event!(Level::TRACE, "r_mat: {}", r_mat);
@@ -241,6 +241,18 @@ mod tests {
                 Ok(())
         }
 
+        #[test]
+        fn test_bad_component_line_is_an_error() {
+                let input = indoc! {"
+                        LLR
+
+                        AAA = (BBB, BBB)
+                        BBB -> AAA
+                        ZZZ = (ZZZ, ZZZ)
+                "};
+                assert!(matches!(process(input), Err(AocErrorDay08::GraphComponent(_))));
+        }
+
         /// NOTE: Debug-Mode vs Release has a huuuuge impact on speed
         /// This test's expected value is to be populated after
         /// verification of solution.
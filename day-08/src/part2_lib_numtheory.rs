@@ -224,7 +224,7 @@ impl IndependentPath {
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> Result<usize> {
         let prob = {
-                let (dirs, (l_mat, r_mat), (start_idxs, solution_idxs)) = process_input(input);
+                let (dirs, (l_mat, r_mat), (start_idxs, solution_idxs)) = process_input(input)?;
                 ProblemSpecifics::new(dirs, start_idxs, solution_idxs, l_mat, r_mat)
         };
         let transitions = {
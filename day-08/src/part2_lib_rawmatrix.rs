@@ -163,7 +163,7 @@ use crate::{
 ///      0      0       0   3   0       0
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> Result<usize, AocErrorDay08> {
-        let (dirs, (l_mat, r_mat), (start_idxs, solution_idxs)) = process_input(input);
+        let (dirs, (l_mat, r_mat), (start_idxs, solution_idxs)) = process_input(input)?;
         // [A, AB, ABC, ... AB..Z]
         let start_to_x_trips = dirs_to_paths(&dirs, (&l_mat, &r_mat));
 